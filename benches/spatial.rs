@@ -0,0 +1,151 @@
+// Compares the nested-loop proximity scans `game()` used to do against the
+// spatial grids, for a swarm of monsters, a cloud of projectiles reacting
+// with each other and the player walking over a field of pickups.
+//
+//     cargo bench --bench spatial
+
+use std::{collections::HashMap, hint::black_box, time::Instant};
+
+use nalgebra::{vector, Point2};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[path = "../src/entity/spatial.rs"]
+#[allow(dead_code, unused_imports)]
+mod spatial;
+
+use spatial::SpatialGrid;
+
+const ARENA: f64 = 200.;
+const ROUNDS: u32 = 20;
+
+struct World {
+    ids: Vec<u64>,
+    monsters: Vec<Point2<f64>>,
+    objects: Vec<Point2<f64>>,
+    items: Vec<Point2<f64>>,
+    // Kept between ticks like the game's
+    item_grid: SpatialGrid<usize>,
+    player: Point2<f64>,
+}
+
+type Tick = fn(&mut World) -> usize;
+
+fn scatter(rng: &mut StdRng, count: usize) -> Vec<Point2<f64>> {
+    (0..count)
+        .map(|_| Point2::new(rng.gen::<f64>() * ARENA, rng.gen::<f64>() * ARENA))
+        .collect()
+}
+
+fn naive(world: &mut World) -> usize {
+    let mut hits = 0;
+    for (object_ix, object) in world.objects.iter().enumerate() {
+        hits += world.objects[object_ix + 1..]
+            .iter()
+            .filter(|other| (*other - object).magnitude() < 0.8)
+            .count();
+
+        for (ix, monster) in world.monsters.iter().enumerate() {
+            if (monster - object).magnitude() < 1. {
+                let id = world.ids[ix];
+                hits += world.ids.iter().position(|other| *other == id).unwrap();
+            }
+        }
+    }
+
+    for ix in 0..world.monsters.len() {
+        let next = world.monsters[ix] + vector![0.1, 0.];
+        let blocked = world
+            .monsters
+            .iter()
+            .enumerate()
+            .any(|(other_ix, other)| other_ix != ix && (other - next).magnitude() < 1.2);
+        if !blocked {
+            world.monsters[ix] = next;
+        }
+    }
+
+    let next = world.player + vector![0.1, 0.];
+    hits += world
+        .items
+        .iter()
+        .filter(|item| (*item - next).magnitude() < 1.)
+        .count();
+    world.player = next;
+
+    hits
+}
+
+fn grid(world: &mut World) -> usize {
+    let mut monster_grid = SpatialGrid::build(2., world.monsters.iter().copied().enumerate());
+    let index: HashMap<u64, usize> = world
+        .ids
+        .iter()
+        .enumerate()
+        .map(|(ix, id)| (*id, ix))
+        .collect();
+    let object_grid = SpatialGrid::build(2., world.objects.iter().copied().enumerate());
+
+    let mut hits = 0;
+    for (object_ix, object) in world.objects.iter().enumerate() {
+        hits += object_grid
+            .nearby(*object, 0.8)
+            .iter()
+            .filter(|(other_ix, _)| *other_ix > object_ix)
+            .count();
+
+        for (ix, _) in monster_grid.nearby(*object, 1.) {
+            hits += index[&world.ids[ix]];
+        }
+    }
+
+    for (ix, monster) in world.monsters.iter_mut().enumerate() {
+        let next = *monster + vector![0.1, 0.];
+        let blocked = monster_grid
+            .nearby(next, 1.2)
+            .iter()
+            .any(|(other_ix, _)| *other_ix != ix);
+        if !blocked {
+            monster_grid.relocate(ix, *monster, next);
+            *monster = next;
+        }
+    }
+
+    let next = world.player + vector![0.1, 0.];
+    hits += world.item_grid.along(world.player, next, 1.).len();
+    world.player = next;
+
+    hits
+}
+
+fn measure(name: &str, count: usize, tick: Tick) -> f64 {
+    let mut rng = StdRng::seed_from_u64(count as u64);
+    let items = scatter(&mut rng, count);
+    let mut world = World {
+        ids: (0..count).map(|_| rng.gen()).collect(),
+        monsters: scatter(&mut rng, count),
+        objects: scatter(&mut rng, count),
+        item_grid: SpatialGrid::build(2., items.iter().copied().enumerate()),
+        items,
+        player: Point2::new(ARENA / 2., ARENA / 2.),
+    };
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(tick(&mut world));
+    }
+    let per_tick = start.elapsed().as_secs_f64() * 1000. / ROUNDS as f64;
+
+    println!(
+        "{:>5} entities  {:<5} {:>9.3} ms/tick",
+        count, name, per_tick
+    );
+    per_tick
+}
+
+fn main() {
+    for count in [100, 1_000, 2_000, 5_000] {
+        let naive = measure("naive", count, naive);
+        let grid = measure("grid", count, grid);
+        println!("{:>5} entities  speedup {:.1}x", count, naive / grid);
+    }
+}
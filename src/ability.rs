@@ -0,0 +1,56 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    ally::Ally,
+    magic::{
+        progression::{Progression, Upgrade, ALL_UPGRADES},
+        resource::Resource,
+        Spell,
+    },
+    object::Object,
+};
+
+pub mod arrow;
+pub mod summon;
+pub mod sweep;
+
+// What an evoked ability brings into play
+#[derive(Default)]
+pub struct Evocation {
+    pub objects: Vec<Box<dyn Object>>,
+    pub allies: Vec<Ally>,
+}
+
+impl From<Vec<Box<dyn Object>>> for Evocation {
+    fn from(objects: Vec<Box<dyn Object>>) -> Self {
+        Self {
+            objects,
+            allies: Vec::new(),
+        }
+    }
+}
+
+pub trait Ability {
+    fn cost(&self) -> u32;
+    fn cooldown(&self) -> u128;
+    fn last_evoke(&self) -> Option<u128>;
+    // `direction` points from the caster at the target, its length is how
+    // far away the target is
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation;
+    fn get_spell(&self) -> Spell;
+    fn progression(&self) -> &Progression;
+    fn progression_mut(&mut self) -> &mut Progression;
+    fn resource(&self) -> &Resource;
+    fn resource_mut(&mut self) -> &mut Resource;
+
+    fn upgrades(&self) -> Vec<Upgrade> {
+        ALL_UPGRADES
+            .into_iter()
+            .filter(|upgrade| self.progression().can_apply(*upgrade))
+            .filter(|upgrade| {
+                // Charges are spent whole, there is no cost to lower
+                *upgrade != Upgrade::LowerCost || !matches!(self.resource(), Resource::Charges(_))
+            })
+            .collect()
+    }
+}
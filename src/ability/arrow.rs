@@ -0,0 +1,88 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    ability::{Ability, Evocation},
+    magic::{
+        emission::{compose, launch, Pattern},
+        progression::Progression,
+        projectile::Projectile,
+        resource::{Charges, Resource},
+        Spell,
+    },
+};
+
+pub fn arrow(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Projectile {
+    Projectile::new(Spell::Arrow, 0.03, location, direction, ticker)
+}
+
+#[derive(Debug)]
+pub struct ArrowAbility {
+    last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
+}
+
+impl ArrowAbility {
+    pub fn new() -> Self {
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Charges(Charges::new(6, 1_500)),
+        }
+    }
+}
+
+impl Ability for ArrowAbility {
+    fn cost(&self) -> u32 {
+        1
+    }
+
+    fn cooldown(&self) -> u128 {
+        self.progression.cooldown(250)
+    }
+
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
+        self.last_evoke = Some(ticker);
+
+        let count = self.progression.projectiles();
+        launch(
+            compose(
+                &[Pattern::Cone {
+                    count,
+                    angle: 0.15 * (count - 1) as f64,
+                }],
+                direction,
+            ),
+            location,
+            ticker,
+            |location, direction, ticker| {
+                arrow(location, direction, ticker).with_pierce(self.progression.piercing())
+            },
+        )
+        .into()
+    }
+
+    fn get_spell(&self) -> Spell {
+        Spell::Arrow
+    }
+
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
+    }
+
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
+    }
+}
@@ -0,0 +1,86 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    ability::{Ability, Evocation},
+    ally::Ally,
+    magic::{
+        emission::{compose, Pattern},
+        progression::{Progression, Upgrade, ALL_UPGRADES},
+        resource::Resource,
+        Spell,
+    },
+};
+
+#[derive(Debug)]
+pub struct SummonAbility {
+    last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
+}
+
+impl SummonAbility {
+    pub fn new() -> Self {
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Blood,
+        }
+    }
+}
+
+impl Ability for SummonAbility {
+    fn cost(&self) -> u32 {
+        self.progression.cost(15)
+    }
+
+    fn cooldown(&self) -> u128 {
+        self.progression.cooldown(1_500)
+    }
+
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
+        self.last_evoke = Some(ticker);
+
+        let count = self.progression.projectiles();
+        let allies = compose(&[Pattern::Wall { count, spacing: 1. }], direction)
+            .into_iter()
+            .map(|emission| Ally::wraith(location + emission.offset, ticker))
+            .collect();
+        Evocation {
+            objects: Vec::new(),
+            allies,
+        }
+    }
+
+    fn get_spell(&self) -> Spell {
+        Spell::Summon
+    }
+
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
+    }
+
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
+    }
+
+    fn upgrades(&self) -> Vec<Upgrade> {
+        // Wraiths already pass through monsters
+        ALL_UPGRADES
+            .into_iter()
+            .filter(|upgrade| *upgrade != Upgrade::Piercing)
+            .filter(|upgrade| self.progression.can_apply(*upgrade))
+            .collect()
+    }
+}
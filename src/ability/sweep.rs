@@ -0,0 +1,94 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    ability::{Ability, Evocation},
+    magic::{
+        emission::{compose, launch, Pattern},
+        progression::{Progression, Upgrade, ALL_UPGRADES},
+        projectile::Projectile,
+        resource::Resource,
+        Spell,
+    },
+};
+
+const SWEEP_DURATION: u128 = 200;
+
+// The arc cuts through everything it touches until it fades
+pub fn sweep(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Projectile {
+    Projectile::new(Spell::Sweep, 0.005, location, direction, ticker)
+        .with_lifetime(SWEEP_DURATION)
+        .with_pierce(true)
+}
+
+#[derive(Debug)]
+pub struct SweepAbility {
+    last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
+}
+
+impl SweepAbility {
+    pub fn new() -> Self {
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Energy,
+        }
+    }
+}
+
+impl Ability for SweepAbility {
+    fn cost(&self) -> u32 {
+        self.progression.cost(8)
+    }
+
+    fn cooldown(&self) -> u128 {
+        self.progression.cooldown(500)
+    }
+
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
+        self.last_evoke = Some(ticker);
+
+        let count = 2 + self.progression.projectiles();
+        launch(
+            compose(&[Pattern::Cone { count, angle: 2. }], direction),
+            location,
+            ticker,
+            sweep,
+        )
+        .into()
+    }
+
+    fn get_spell(&self) -> Spell {
+        Spell::Sweep
+    }
+
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
+    }
+
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
+    }
+
+    fn upgrades(&self) -> Vec<Upgrade> {
+        // Already cuts through everything
+        ALL_UPGRADES
+            .into_iter()
+            .filter(|upgrade| *upgrade != Upgrade::Piercing)
+            .filter(|upgrade| self.progression.can_apply(*upgrade))
+            .collect()
+    }
+}
@@ -0,0 +1,154 @@
+use std::io;
+
+use crate::{
+    console::{AsSymbol, Keymap},
+    display::Display,
+    loadout,
+    magic::{Spell, KNOWN_SPELLS},
+    menu,
+    stats::{Modifier, Stat},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Class {
+    Wizard,
+    Ranger,
+    Warrior,
+    Necromancer,
+}
+
+pub const CLASSES: [Class; 4] = [
+    Class::Wizard,
+    Class::Ranger,
+    Class::Warrior,
+    Class::Necromancer,
+];
+
+impl Class {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Class::Wizard => "picks a spellbook, balanced stats",
+            Class::Ranger => "quick on their feet, arrows from a quiver",
+            Class::Warrior => "tough and armored, melee sweeps",
+            Class::Necromancer => "deep energy, summons wraiths with blood",
+        }
+    }
+
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        match self {
+            Class::Wizard => vec![],
+            Class::Ranger => vec![
+                Modifier::Add(Stat::MoveSpeed, 1.5),
+                Modifier::Add(Stat::MaxHealth, -20.),
+            ],
+            Class::Warrior => vec![
+                Modifier::Add(Stat::MaxHealth, 50.),
+                Modifier::Add(Stat::Armor, 5.),
+                Modifier::Add(Stat::MaxEnergy, -40.),
+                Modifier::Add(Stat::MoveSpeed, -0.5),
+            ],
+            Class::Necromancer => vec![
+                Modifier::Add(Stat::MaxEnergy, 30.),
+                Modifier::Add(Stat::Armor, -3.),
+            ],
+        }
+    }
+
+    pub fn starting_abilities(&self) -> Vec<Spell> {
+        match self {
+            Class::Wizard => loadout::DEFAULT_LOADOUT.to_vec(),
+            Class::Ranger => vec![Spell::Arrow, Spell::Sphere],
+            Class::Warrior => vec![Spell::Sweep, Spell::Inferno],
+            Class::Necromancer => vec![Spell::Summon, Spell::Orb],
+        }
+    }
+
+    // Abilities that can be learned when leveling up
+    pub fn learnable(&self) -> Vec<Spell> {
+        match self {
+            Class::Wizard => KNOWN_SPELLS.to_vec(),
+            Class::Ranger => vec![Spell::Arrow, Spell::Sphere, Spell::Spark, Spell::Fireball],
+            Class::Warrior => vec![Spell::Sweep, Spell::Inferno, Spell::Fireball],
+            Class::Necromancer => vec![Spell::Summon, Spell::Orb, Spell::Spark, Spell::Sphere],
+        }
+    }
+}
+
+// Returns the chosen class and its starting abilities, wizards pick a spellbook
+pub fn choose_class(
+    display: &mut impl Display,
+    keymap: &Keymap,
+) -> io::Result<(Class, Vec<Spell>)> {
+    let options: Vec<String> = CLASSES
+        .iter()
+        .map(|class| {
+            format!(
+                "{} {:<12} {}",
+                class.as_symbol(),
+                format!("{:?}", class),
+                class.description()
+            )
+        })
+        .collect();
+
+    let class = menu::choose(display, keymap, "Choose your class", &options)?
+        .map_or(Class::Wizard, |ix| CLASSES[ix]);
+
+    let abilities = match class {
+        Class::Wizard => loadout::choose_loadout(display, keymap)?,
+        _ => class.starting_abilities(),
+    };
+
+    Ok((class, abilities))
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Point2;
+
+    use crate::{magic::resource::Resource, player::Player};
+
+    use super::*;
+
+    fn player(class: Class) -> Player {
+        Player::new(Point2::new(5., 5.), 0, class, &class.starting_abilities())
+    }
+
+    #[test]
+    fn classes_start_with_their_own_stats() {
+        let wizard = player(Class::Wizard);
+        let ranger = player(Class::Ranger);
+        let warrior = player(Class::Warrior);
+        let necromancer = player(Class::Necromancer);
+
+        assert!(warrior.max_health() > wizard.max_health());
+        assert!(ranger.max_health() < wizard.max_health());
+        assert!(warrior.stat(Stat::Armor) > wizard.stat(Stat::Armor));
+        assert!(necromancer.stat(Stat::Armor) < wizard.stat(Stat::Armor));
+        assert!(warrior.max_energy() < wizard.max_energy());
+        assert!(necromancer.max_energy() > wizard.max_energy());
+        assert!(ranger.stat(Stat::MoveSpeed) > warrior.stat(Stat::MoveSpeed));
+        for player in [wizard, ranger, warrior, necromancer] {
+            assert_eq!(player.health, player.max_health());
+            assert_eq!(player.energy, player.max_energy());
+        }
+    }
+
+    #[test]
+    fn classes_start_with_their_own_abilities() {
+        for class in CLASSES {
+            let spells: Vec<Spell> = player(class)
+                .spells
+                .iter()
+                .map(|spell| spell.get_spell())
+                .collect();
+            assert_eq!(spells, class.starting_abilities());
+            assert!(spells.iter().all(|spell| class.learnable().contains(spell)));
+        }
+
+        let ranger = player(Class::Ranger);
+        assert!(matches!(ranger.spells[0].resource(), Resource::Charges(_)));
+        let necromancer = player(Class::Necromancer);
+        assert_eq!(necromancer.spells[0].resource(), &Resource::Blood);
+    }
+}
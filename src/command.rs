@@ -1,13 +1,169 @@
-use crate::direction::Direction;
+use crate::{direction::Direction, item::Item};
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Command {
     Move(Direction),
     Evoke(Direction),
     EvokeMouse,
+    EvokeSecondary,
     CycleSpell(bool),
     SelectSpell(usize),
+    UseItem(Item),
+    BindSecondary,
+    Swing,
+    Dash,
+    Pause,
+    Quit,
+    DebugInfo,
+    Rebind,
+    DevConsole,
+    FastForward,
+    Step,
 }
 
-pub trait AsCommand {
-    fn as_command(&self) -> Option<Command>;
+// Every command that can be bound, in the order they are listed in the keymap
+pub const COMMANDS: [Command; 33] = [
+    Command::Move(Direction::Up),
+    Command::Move(Direction::Left),
+    Command::Move(Direction::Down),
+    Command::Move(Direction::Right),
+    Command::Evoke(Direction::Up),
+    Command::Evoke(Direction::Left),
+    Command::Evoke(Direction::Down),
+    Command::Evoke(Direction::Right),
+    Command::EvokeMouse,
+    Command::EvokeSecondary,
+    Command::Swing,
+    Command::Dash,
+    Command::SelectSpell(0),
+    Command::SelectSpell(1),
+    Command::SelectSpell(2),
+    Command::SelectSpell(3),
+    Command::SelectSpell(4),
+    Command::SelectSpell(5),
+    Command::SelectSpell(6),
+    Command::SelectSpell(7),
+    Command::SelectSpell(8),
+    Command::CycleSpell(false),
+    Command::CycleSpell(true),
+    Command::BindSecondary,
+    Command::UseItem(Item::HealthPotion),
+    Command::UseItem(Item::EnergyPotion),
+    Command::Pause,
+    Command::Quit,
+    Command::DebugInfo,
+    Command::Rebind,
+    Command::DevConsole,
+    Command::FastForward,
+    Command::Step,
+];
+
+// Headings the key bindings are listed under, in order
+pub const GROUPS: [&str; 5] = [
+    "Movement",
+    "Attacks",
+    "Spell slots",
+    "Spells and items",
+    "System",
+];
+
+impl Command {
+    // Name used in the keymap file
+    pub fn name(&self) -> String {
+        match self {
+            Command::Move(direction) => format!("move_{}", direction_name(direction)),
+            Command::Evoke(direction) => format!("evoke_{}", direction_name(direction)),
+            Command::EvokeMouse => String::from("evoke_mouse"),
+            Command::EvokeSecondary => String::from("evoke_secondary"),
+            Command::CycleSpell(false) => String::from("previous_spell"),
+            Command::CycleSpell(true) => String::from("next_spell"),
+            Command::SelectSpell(ix) => format!("spell_{}", ix + 1),
+            Command::BindSecondary => String::from("bind_secondary"),
+            Command::UseItem(Item::HealthPotion) => String::from("health_potion"),
+            Command::UseItem(Item::EnergyPotion) => String::from("energy_potion"),
+            Command::UseItem(item) => format!("{:?}", item).to_lowercase(),
+            Command::Swing => String::from("swing"),
+            Command::Dash => String::from("dash"),
+            Command::Pause => String::from("pause"),
+            Command::Quit => String::from("quit"),
+            Command::DebugInfo => String::from("debug_info"),
+            Command::Rebind => String::from("rebind"),
+            Command::DevConsole => String::from("dev_console"),
+            Command::FastForward => String::from("fast_forward"),
+            Command::Step => String::from("step"),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Command> {
+        COMMANDS.into_iter().find(|command| command.name() == name)
+    }
+
+    // One of `GROUPS`
+    pub fn group(&self) -> &'static str {
+        match self {
+            Command::Move(_) => "Movement",
+            Command::Evoke(_)
+            | Command::EvokeMouse
+            | Command::EvokeSecondary
+            | Command::Swing
+            | Command::Dash => "Attacks",
+            Command::SelectSpell(_) => "Spell slots",
+            Command::CycleSpell(_) | Command::BindSecondary | Command::UseItem(_) => {
+                "Spells and items"
+            }
+            Command::Pause
+            | Command::Quit
+            | Command::DebugInfo
+            | Command::Rebind
+            | Command::DevConsole
+            | Command::FastForward
+            | Command::Step => "System",
+        }
+    }
+
+    // Fires every tick while its input is held rather than once per press
+    pub fn is_held(&self) -> bool {
+        matches!(
+            self,
+            Command::Move(_) | Command::Evoke(_) | Command::EvokeMouse | Command::EvokeSecondary
+        )
+    }
+
+    // Handled as soon as the input arrives, even while the game is paused
+    pub fn is_system(&self) -> bool {
+        matches!(
+            self,
+            Command::Pause
+                | Command::Quit
+                | Command::DebugInfo
+                | Command::Rebind
+                | Command::DevConsole
+                | Command::FastForward
+                | Command::Step
+        )
+    }
+}
+
+fn direction_name(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_group_lists_commands() {
+        for command in COMMANDS {
+            assert!(GROUPS.contains(&command.group()), "{:?}", command);
+        }
+        for group in GROUPS {
+            assert!(COMMANDS.iter().any(|command| command.group() == group));
+        }
+    }
 }
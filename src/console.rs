@@ -1,29 +1,37 @@
 mod as_color;
 mod as_symbol;
-mod command;
 mod console_display;
 mod console_unit;
+mod dev_console;
+mod effects;
+mod glyphs;
 mod input;
 mod input_tracker;
+mod keymap;
+mod palette;
+mod screen;
 
 pub use self::as_color::AsColor;
 pub use self::as_symbol::AsSymbol;
-pub use self::console_display::ConsoleDisplay;
+pub use self::console_display::{ConsoleDisplay, SIDE_PANEL_WIDTH};
 pub use self::console_unit::ConsoleUnit;
-pub use self::input_tracker::InputTracker;
+pub use self::dev_console::DevConsole;
+pub use self::glyphs::{padded, Glyphs};
+pub use self::input_tracker::{InputTracker, KeyReleases};
+pub use self::keymap::Keymap;
+pub use self::palette::{ColorDepth, Palette, Theme};
 
-const LOADING_SYMBOLS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
-
-#[allow(dead_code)]
 pub fn loader(current: u128, target: u128, range: u128) -> char {
+    let loading_symbols = Glyphs::current().loading();
     let val = ((range.saturating_sub(target.saturating_sub(current))) as f32 / range as f32
-        * LOADING_SYMBOLS.len() as f32)
-        .clamp(0.0, (LOADING_SYMBOLS.len() - 1) as f32) as usize;
-    LOADING_SYMBOLS[val]
+        * loading_symbols.len() as f32)
+        .clamp(0.0, (loading_symbols.len() - 1) as f32) as usize;
+    loading_symbols[val]
 }
 pub fn loader_reverse(current: u128, target: u128, range: u128) -> char {
+    let loading_symbols = Glyphs::current().loading();
     let val = ((range.saturating_sub(target.saturating_sub(current))) as f32 / range as f32
-        * LOADING_SYMBOLS.len() as f32)
-        .clamp(0.0, (LOADING_SYMBOLS.len() - 1) as f32) as usize;
-    LOADING_SYMBOLS[LOADING_SYMBOLS.len() - val - 1]
+        * loading_symbols.len() as f32)
+        .clamp(0.0, (loading_symbols.len() - 1) as f32) as usize;
+    loading_symbols[loading_symbols.len() - val - 1]
 }
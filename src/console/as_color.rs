@@ -1,6 +1,10 @@
 use crossterm::style::Color;
 
-use crate::magic::Spell;
+use crate::{
+    equipment::Gear,
+    item::Item,
+    magic::{element::Element, Spell},
+};
 
 pub trait AsColor {
     fn as_color(&self) -> Color;
@@ -12,6 +16,36 @@ impl AsColor for Spell {
             Spell::Fireball => Color::Red,
             Spell::Sphere => Color::Blue,
             Spell::Inferno => Color::Red,
+            Spell::Spark => Color::Yellow,
+            Spell::Orb => Color::Magenta,
+            Spell::Steam => Color::White,
+            Spell::Arrow => Color::DarkYellow,
+            Spell::Sweep => Color::Grey,
+            Spell::Summon => Color::DarkGreen,
+            Spell::Venom => Color::Green,
+        }
+    }
+}
+
+impl AsColor for Item {
+    fn as_color(&self) -> Color {
+        match self {
+            Item::HealthPotion => Color::Red,
+            Item::EnergyPotion => Color::Green,
+            Item::Gear(Gear::SwiftRing) => Color::Cyan,
+            Item::Gear(Gear::FocusRing) => Color::Magenta,
+            Item::Gear(_) => Color::Yellow,
+        }
+    }
+}
+
+impl AsColor for Element {
+    fn as_color(&self) -> Color {
+        match self {
+            Element::Fire => Color::Red,
+            Element::Frost => Color::Cyan,
+            Element::Lightning => Color::Yellow,
+            Element::Arcane => Color::Magenta,
         }
     }
 }
@@ -1,15 +1,108 @@
-use crate::magic::Spell;
+use crate::{class::Class, equipment::Gear, item::Item, magic::Spell};
+
+use super::Glyphs;
 
 pub trait AsSymbol {
-    fn as_symbol(&self) -> char;
+    fn glyph(&self, glyphs: Glyphs) -> char;
+
+    fn as_symbol(&self) -> char {
+        self.glyph(Glyphs::current())
+    }
 }
 
 impl AsSymbol for Spell {
-    fn as_symbol(&self) -> char {
-        match self {
-            Spell::Fireball => '🔥',
-            Spell::Sphere => '🔵',
-            Spell::Inferno => '🎆',
+    fn glyph(&self, glyphs: Glyphs) -> char {
+        match glyphs {
+            Glyphs::Emoji => match self {
+                Spell::Fireball => '🔥',
+                Spell::Sphere => '🔵',
+                Spell::Inferno => '🎆',
+                Spell::Spark => '⚡',
+                Spell::Orb => '🔮',
+                Spell::Steam => '💨',
+                Spell::Arrow => '🏹',
+                Spell::Sweep => '🌀',
+                Spell::Summon => '👻',
+                Spell::Venom => '🟢',
+            },
+            Glyphs::Unicode => match self {
+                Spell::Fireball => '☼',
+                Spell::Sphere => '●',
+                Spell::Inferno => '✺',
+                Spell::Spark => 'ϟ',
+                Spell::Orb => '◎',
+                Spell::Steam => '≈',
+                Spell::Arrow => '➶',
+                Spell::Sweep => '◠',
+                Spell::Summon => 'Ѡ',
+                Spell::Venom => '•',
+            },
+            Glyphs::Ascii => match self {
+                Spell::Fireball => '*',
+                Spell::Sphere => 'o',
+                Spell::Inferno => '#',
+                Spell::Spark => ';',
+                Spell::Orb => '0',
+                Spell::Steam => '~',
+                Spell::Arrow => '>',
+                Spell::Sweep => ')',
+                Spell::Summon => 'W',
+                Spell::Venom => '^',
+            },
+        }
+    }
+}
+
+impl AsSymbol for Class {
+    fn glyph(&self, glyphs: Glyphs) -> char {
+        match glyphs {
+            Glyphs::Emoji => match self {
+                Class::Wizard => '🧙',
+                Class::Ranger => '🧝',
+                Class::Warrior => '🤺',
+                Class::Necromancer => '🧛',
+            },
+            Glyphs::Unicode | Glyphs::Ascii => '@',
+        }
+    }
+}
+
+impl AsSymbol for Item {
+    fn glyph(&self, glyphs: Glyphs) -> char {
+        match glyphs {
+            Glyphs::Emoji => match self {
+                Item::HealthPotion => '🍷',
+                Item::EnergyPotion => '🧪',
+                Item::Gear(gear) => match gear {
+                    Gear::OakStaff => '🪄',
+                    Gear::SilkRobe => '👘',
+                    Gear::MoonAmulet => '📿',
+                    Gear::SwiftRing => '💍',
+                    Gear::FocusRing => '💎',
+                },
+            },
+            Glyphs::Unicode => match self {
+                Item::HealthPotion => '♥',
+                Item::EnergyPotion => '♦',
+                Item::Gear(gear) => match gear {
+                    Gear::OakStaff => '⌠',
+                    Gear::SilkRobe => 'Ω',
+                    Gear::MoonAmulet => '♁',
+                    Gear::SwiftRing => '°',
+                    Gear::FocusRing => '◇',
+                },
+            },
+            Glyphs::Ascii => match self {
+                Item::HealthPotion => '!',
+                Item::EnergyPotion => '?',
+                Item::Gear(gear) => match gear {
+                    Gear::OakStaff => '/',
+                    Gear::SilkRobe => '[',
+                    Gear::MoonAmulet => '"',
+                    Gear::SwiftRing => '=',
+                    Gear::FocusRing => '$',
+                },
+            },
         }
     }
 }
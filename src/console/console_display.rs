@@ -3,16 +3,26 @@ use std::{
     io,
 };
 
-use crossterm::{
-    cursor, execute,
-    style::{self, Color, Stylize},
-    terminal,
-};
+use crossterm::{execute, style::Color, terminal};
 
 use nalgebra::{vector, Point2, Scale2, Vector2};
+use unicode_width::UnicodeWidthStr;
 
-use super::{loader, loader_reverse, AsColor, AsSymbol, ConsoleUnit};
-use crate::{display::Display, player::Player, render_action::RenderAction, Entity, State};
+use super::{
+    effects::{ActiveEffect, Anchor},
+    loader, loader_reverse, padded,
+    screen::Screen,
+    AsColor, AsSymbol, ConsoleUnit, Glyphs,
+};
+use crate::{
+    ability::Ability,
+    display::Display,
+    item::{Item, CONSUMABLES},
+    magic::resource::Resource,
+    player::Player,
+    render_action::{Effect, RenderAction},
+    Entity, State, DOOR,
+};
 
 pub struct ConsoleDisplay<'a> {
     pub status_indicators: HashMap<&'a str, Indicator>,
@@ -22,6 +32,8 @@ pub struct ConsoleDisplay<'a> {
     stdout: &'a mut io::Stdout,
     render_actions: VecDeque<RenderAction>,
     game_area_offset: Vector2<u16>,
+    screen: Screen,
+    effects: Vec<ActiveEffect>,
 }
 
 pub struct Indicator {
@@ -60,30 +72,87 @@ impl AsPoint2 for Point2<f64> {
     }
 }
 
+// Columns right of the frame taken by the inventory, a blank one then the
+// widest of "Items" and a symbol with its count
+pub const SIDE_PANEL_WIDTH: u16 = 6;
+
+// Terminal cells taken by the frame and the inventory beside it
+fn footprint(dimensions: Vector2<u16>) -> Vector2<u16> {
+    dimensions + vector![1 + SIDE_PANEL_WIDTH, 1]
+}
+
+fn fits(terminal: Vector2<u16>, dimensions: Vector2<u16>) -> bool {
+    let needed = footprint(dimensions);
+    terminal.x >= needed.x && terminal.y >= needed.y
+}
+
+// Frame and inventory centered horizontally, a little above the middle
+fn layout(terminal: Vector2<u16>, dimensions: Vector2<u16>) -> Point2<u16> {
+    let footprint = footprint(dimensions);
+    Point2::new(
+        terminal.x.saturating_sub(footprint.x) / 2,
+        terminal.y.saturating_sub(footprint.y) / 3,
+    )
+}
+
+// Cells taken by each indicator on the frame, see `draw_state`. The spell
+// bar gets whatever is left of the bottom edge
+const SCORE_WIDTH: u16 = 3;
+const FLOOR_WIDTH: u16 = 3;
+const COMBO_WIDTH: u16 = 4;
+const CLOCK_WIDTH: u16 = 3;
+const HEALTH_WIDTH: u16 = 7;
+const ENERGY_WIDTH: u16 = 8;
+
+// Placed one after another from the corners of the frame
+fn status_indicators<'a>(
+    top_left: Point2<u16>,
+    dimensions: Vector2<u16>,
+) -> HashMap<&'a str, Indicator> {
+    let bottom_right = top_left + dimensions;
+    let top_right = top_left + Vector2::new(dimensions.x, 0);
+    let bottom_left = top_left + Vector2::new(0, dimensions.y);
+
+    let score = top_left + Vector2::new(4, 0);
+    let floor = score + Vector2::new(SCORE_WIDTH + 3, 0);
+    let level = floor + Vector2::new(FLOOR_WIDTH + 2, 0);
+    let clock = top_right - Vector2::new(CLOCK_WIDTH + 3, 0);
+    let combo = clock - Vector2::new(COMBO_WIDTH + 2, 0);
+    let energy = bottom_right - Vector2::new(ENERGY_WIDTH + 1, 0);
+    let health = energy - Vector2::new(HEALTH_WIDTH + 1, 0);
+
+    HashMap::from([
+        ("clock", Indicator::new(clock)),
+        ("score", Indicator::new(score)),
+        ("floor", Indicator::new(floor)),
+        ("level", Indicator::new(level)),
+        ("spells", Indicator::new(bottom_left + Vector2::new(4, 0))),
+        ("energy", Indicator::new(energy)),
+        ("health", Indicator::new(health)),
+        ("combo", Indicator::new(combo)),
+        ("inventory", Indicator::new(top_right + Vector2::new(2, 1))),
+    ])
+}
+
 impl<'a> ConsoleDisplay<'a> {
     pub fn new(
-        top_left: Point2<u16>,
+        terminal: Vector2<u16>,
         dimensions: Vector2<u16>,
         resolution: Scale2<u16>,
         stdout: &'a mut io::Stdout,
     ) -> Self {
-        let bottom_right = top_left + dimensions;
-        let top_right = top_left + Vector2::new(dimensions.x, 0);
-        let bottom_left = top_left + Vector2::new(0, dimensions.y);
+        let top_left = layout(terminal, dimensions);
 
         Self {
             stdout,
             top_left,
             dimensions,
             resolution,
-            status_indicators: HashMap::from([
-                ("clock", Indicator::new(top_right - Vector2::new(6, 0))),
-                ("score", Indicator::new(top_left + Vector2::new(4, 0))),
-                ("spells", Indicator::new(bottom_left + Vector2::new(4, 0))),
-                ("energy", Indicator::new(bottom_right - Vector2::new(9, 0))),
-            ]),
+            status_indicators: status_indicators(top_left, dimensions),
             render_actions: VecDeque::new(),
             game_area_offset: top_left - Point2::new(0, 0) + Vector2::new(1, 1),
+            screen: Screen::new(terminal),
+            effects: Vec::new(),
         }
     }
 
@@ -91,7 +160,15 @@ impl<'a> ConsoleDisplay<'a> {
         self.game_area_offset
     }
 
-    fn draw_actions(&mut self) -> io::Result<()> {
+    fn draw_actions(&mut self, state: &State) {
+        // Items and the door sit underneath units, restore them instead of blanking
+        let statics: HashMap<Point2<u16>, (char, Color)> = state
+            .items
+            .iter()
+            .map(|item| (item.location().as_point2(), (item.symbol(), item.color())))
+            .chain([(DOOR.as_point2(), (Glyphs::current().door(), Color::White))])
+            .collect();
+
         let mut clear: HashSet<Point2<u16>> = HashSet::new();
         let mut skip_clear: HashSet<Point2<u16>> = HashSet::new();
         let mut renders = Vec::new();
@@ -119,71 +196,277 @@ impl<'a> ConsoleDisplay<'a> {
                     skip_clear.insert(coord.as_point2());
                     renders.push((coord.as_point2(), symbol, color));
                 }
+                RenderAction::Effect {
+                    effect,
+                    color,
+                    location,
+                } => self.effects.push(ActiveEffect {
+                    effect,
+                    color,
+                    anchor: Anchor::Spot(location),
+                    start: state.ticker,
+                }),
+                RenderAction::MonsterEffect { effect, color, id } => {
+                    self.effects.push(ActiveEffect {
+                        effect,
+                        color,
+                        anchor: Anchor::Monster(id),
+                        start: state.ticker,
+                    })
+                }
             };
         }
 
         for coord in clear {
             if !skip_clear.contains(&coord) {
                 let spot = self.resolution * coord + self.game_area_offset;
-                execute!(
-                    self.stdout,
-                    cursor::MoveTo(spot.x, spot.y),
-                    style::PrintStyledContent(' '.on(bg_color(spot))),
-                    style::PrintStyledContent(' '.on(bg_color(spot + vector![1, 0]))),
-                )?;
+                if let Some((symbol, color)) = statics.get(&coord) {
+                    self.put_unit(spot, *symbol, *color);
+                    continue;
+                }
+                self.screen.put(spot, ' ', Color::Reset, bg_color(spot));
+                self.screen.put(
+                    spot + vector![1, 0],
+                    ' ',
+                    Color::Reset,
+                    bg_color(spot + vector![1, 0]),
+                );
             }
         }
 
         for render in renders {
-            match render {
-                (coord, symbol, color) => {
-                    let spot = self.resolution * coord + self.game_area_offset;
-                    execute!(
-                        self.stdout,
-                        cursor::MoveTo(spot.x, spot.y),
-                        style::PrintStyledContent(symbol.with(color).on(bg_color(spot))),
-                    )?;
+            let (coord, symbol, color) = render;
+            let spot = self.resolution * coord + self.game_area_offset;
+            self.put_unit(spot, symbol, color);
+        }
+    }
+
+    // Rebuilds the overlay from the effects still running
+    fn draw_effects(&mut self, state: &State) {
+        let ticker = state.ticker;
+        self.screen.clear_overlay();
+        self.effects.retain(|effect| !effect.expired(ticker));
+
+        for effect in &self.effects {
+            let frame = effect.frame(ticker);
+
+            if effect.effect == Effect::HurtFlash {
+                let (width, height) = (self.dimensions.x, self.dimensions.y);
+                let border = (0..=width)
+                    .flat_map(|x| [vector![x, 0], vector![x, height]])
+                    .chain((1..height).flat_map(|y| [vector![0, y], vector![width, y]]));
+                for offset in border {
+                    self.screen.tint(self.top_left + offset, frame.fg, frame.bg);
                 }
+                continue;
             }
+
+            let location = match effect.anchor {
+                Anchor::Spot(location) => location,
+                // Ends early with the monster it follows
+                Anchor::Monster(id) => match state.monsters.iter().find(|m| m.id == id) {
+                    Some(monster) => monster.location(),
+                    None => continue,
+                },
+            };
+            let center = location.as_point2();
+            let neighbours = [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)];
+            for (dx, dy) in neighbours.iter().take(if frame.spread { 5 } else { 1 }) {
+                let (Some(x), Some(y)) = (
+                    center.x.checked_add_signed(*dx),
+                    center.y.checked_add_signed(*dy),
+                ) else {
+                    continue;
+                };
+                let spot = self.resolution * Point2::new(x, y) + self.game_area_offset;
+                // Stay inside the frame
+                if spot.x + 1 >= self.top_left.x + self.dimensions.x
+                    || spot.y >= self.top_left.y + self.dimensions.y
+                {
+                    continue;
+                }
+                // Trails only show on empty floor
+                if effect.effect == Effect::Trail && !self.screen.is_blank(spot) {
+                    continue;
+                }
+
+                match frame.symbol {
+                    Some(symbol) => {
+                        let fg = frame.fg.unwrap_or(effect.color);
+                        let next = self.screen.overlay(
+                            spot,
+                            symbol,
+                            fg,
+                            frame.bg.unwrap_or(bg_color(spot)),
+                        );
+                        if next == spot.x + 1 {
+                            let pad = spot + vector![1, 0];
+                            self.screen
+                                .overlay(pad, ' ', fg, frame.bg.unwrap_or(bg_color(pad)));
+                        }
+                    }
+                    None => {
+                        self.screen.tint(spot, frame.fg, frame.bg);
+                        self.screen.tint(spot + vector![1, 0], frame.fg, frame.bg);
+                    }
+                }
+            }
+        }
+    }
+
+    // Units span two columns, narrow glyphs are padded with background
+    fn put_unit(&mut self, spot: Point2<u16>, symbol: char, color: Color) {
+        let next = self.screen.put(spot, symbol, color, bg_color(spot));
+        if next == spot.x + 1 {
+            let pad = spot + vector![1, 0];
+            self.screen.put(pad, ' ', Color::Reset, bg_color(pad));
         }
+    }
+
+    fn draw_frame(&mut self) {
+        let (width, height) = (self.dimensions.x, self.dimensions.y);
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] =
+            Glyphs::current().frame();
 
-        Ok(())
+        for y in 0..=height {
+            for x in 0..=width {
+                let border = match (x, y) {
+                    (0, 0) => Some(top_left),
+                    (0, y) if y == height => Some(bottom_left),
+                    (x, 0) if x == width => Some(top_right),
+                    (x, y) if x == width && y == height => Some(bottom_right),
+                    (0, _) => Some(vertical),
+                    (x, _) if x == width => Some(vertical),
+                    (_, 0) => Some(horizontal),
+                    (_, y) if y == height => Some(horizontal),
+                    _ => None,
+                };
+                let spot = self.top_left + vector!(x, y);
+                match border {
+                    Some(symbol) => self.screen.put(spot, symbol, Color::Magenta, Color::Reset),
+                    None => self
+                        .screen
+                        .put(spot, ' ', Color::Reset, bg_color(Point2::new(x, y))),
+                };
+            }
+        }
     }
 
-    fn draw_state(&mut self, state: &State) -> io::Result<()> {
+    fn draw_panel(&mut self, lines: &[(String, Color)]) -> io::Result<()> {
+        self.draw_frame();
+
+        let (width, height) = (self.dimensions.x, self.dimensions.y);
+        let text_width = width.saturating_sub(4) as usize;
+
+        for y in 1..height {
+            let spot = self.top_left + vector!(1, y);
+            self.screen.put_str(
+                spot,
+                &" ".repeat((width - 1).into()),
+                Color::Reset,
+                Color::Black,
+            );
+        }
+
+        for (i, (line, color)) in lines.iter().enumerate() {
+            let spot = self.top_left + vector!(2, 2 + 2 * i as u16);
+            if spot.y >= self.top_left.y + height {
+                break;
+            }
+            self.screen.put_str(
+                spot,
+                &line.chars().take(text_width).collect::<String>(),
+                *color,
+                Color::Black,
+            );
+        }
+
+        self.screen.present(self.stdout)
+    }
+
+    fn draw_state(&mut self, state: &State) {
         draw_value(
-            self.stdout,
+            &mut self.screen,
             self.status_indicators.get("clock"),
             format!("{:>3}", state.ticker / 1000),
-        )?;
+        );
         draw_value(
-            self.stdout,
+            &mut self.screen,
             self.status_indicators.get("score"),
             format!("{:>3}", state.score),
-        )?;
+        );
+        draw_value(
+            &mut self.screen,
+            self.status_indicators.get("floor"),
+            format!("F{:<2}", state.floor),
+        );
+        draw_value(
+            &mut self.screen,
+            self.status_indicators.get("level"),
+            format!(
+                "Lv{:<2} {}",
+                state.player.experience.level,
+                xp_bar(
+                    state.player.experience.xp,
+                    state.player.experience.next_level_xp()
+                )
+            ),
+        );
 
+        // Spells that would run into the health indicator are left out
+        let spells_end = self
+            .status_indicators
+            .get("health")
+            .map_or(u16::MAX, |health| health.coord.x - 1);
         draw_spells(
-            self.stdout,
+            &mut self.screen,
             self.status_indicators.get("spells"),
+            spells_end,
             &state.player,
             state.ticker,
-        )?;
+        );
 
         draw_value(
-            self.stdout,
+            &mut self.screen,
             self.status_indicators.get("energy"),
             format!(
-                "🧪 {:0>3} {}",
+                "{} {:0>3} {}",
+                padded(Item::EnergyPotion.as_symbol()),
                 state.player.energy,
                 loader(
                     state.player.energy.into(),
-                    state.player.max_energy.into(),
-                    state.player.max_energy.into()
+                    state.player.max_energy().into(),
+                    state.player.max_energy().into()
+                )
+            ),
+        );
+
+        draw_value(
+            &mut self.screen,
+            self.status_indicators.get("health"),
+            format!(
+                "{} {:0>3} {}",
+                Glyphs::current().heart(),
+                state.player.health,
+                loader(
+                    state.player.health.into(),
+                    state.player.max_health().into(),
+                    state.player.max_health().into()
                 )
             ),
-        )?;
+        );
 
-        Ok(())
+        draw_value(
+            &mut self.screen,
+            self.status_indicators.get("combo"),
+            format!("{}{:0>3}", Glyphs::current().combo(), state.player.combo),
+        );
+
+        draw_inventory(
+            &mut self.screen,
+            self.status_indicators.get("inventory"),
+            &state.player,
+        );
     }
 }
 
@@ -193,33 +476,11 @@ impl Display for ConsoleDisplay<'_> {
     }
 
     fn draw_initial(&mut self, state: &State) -> io::Result<()> {
-        execute!(self.stdout, terminal::BeginSynchronizedUpdate)?;
-        execute!(self.stdout, terminal::Clear(terminal::ClearType::All))?;
-
-        let (width, height) = (self.dimensions.x, self.dimensions.y);
+        // Repaints everything, in case the terminal drew a glyph wider than expected
+        self.screen.clear();
+        self.screen.invalidate();
 
-        for y in 0..=height {
-            for x in 0..=width {
-                let content = match (x, y) {
-                    // (_, 0) => format!("{}", x % 10).magenta(),
-                    (0, 0) => String::from("╔").magenta(),
-                    (0, y) if y == height => String::from("╚").magenta(),
-                    (x, 0) if x == width => String::from("╗").magenta(),
-                    (x, y) if x == width && y == height => String::from("╝").magenta(),
-                    (0, _) => String::from("║").magenta(),
-                    (x, _) if x == width => String::from("║").magenta(),
-                    (_, 0) => String::from("═").magenta(),
-                    (_, y) if y == height => String::from("═").magenta(),
-                    _ => String::from(" ").on(bg_color(Point2::new(x, y))),
-                };
-                let spot = self.top_left + vector!(x, y);
-                execute!(
-                    self.stdout,
-                    cursor::MoveTo(spot.x, spot.y),
-                    style::PrintStyledContent(content)
-                )?;
-            }
-        }
+        self.draw_frame();
 
         let initial_actions = state
             .monsters
@@ -236,117 +497,324 @@ impl Display for ConsoleDisplay<'_> {
                     location: state.player.location(),
                 },
                 RenderAction::Create {
-                    symbol: '🚪',
+                    symbol: Glyphs::current().door(),
                     color: Color::White,
-                    location: Point2::new(1., 1.),
+                    location: DOOR,
                 },
-            ]);
+            ])
+            .chain(state.items.iter().map(|item| RenderAction::Create {
+                symbol: item.symbol(),
+                color: item.color(),
+                location: item.location(),
+            }))
+            .chain(state.objects.iter().map(|object| RenderAction::Create {
+                symbol: object.symbol(),
+                color: object.color(),
+                location: object.location(),
+            }))
+            .chain(state.allies.iter().map(|ally| RenderAction::Create {
+                symbol: ally.symbol(),
+                color: ally.color(),
+                location: ally.location(),
+            }));
 
         for action in initial_actions {
             self.enqueue_action(action);
         }
 
-        self.draw_actions()?;
+        self.draw_actions(state);
 
-        self.draw_state(state)?;
+        self.draw_effects(state);
 
-        execute!(self.stdout, terminal::EndSynchronizedUpdate)?;
+        self.draw_state(state);
 
-        Ok(())
+        self.screen.present(self.stdout)
     }
 
     fn draw(&mut self, state: &State) -> io::Result<()> {
-        execute!(self.stdout, terminal::BeginSynchronizedUpdate)?;
+        self.draw_actions(state);
+
+        self.draw_effects(state);
+
+        self.draw_state(state);
 
-        self.draw_actions()?;
+        self.screen.present(self.stdout)
+    }
+
+    fn draw_menu(&mut self, title: &str, options: &[String]) -> io::Result<()> {
+        let lines: Vec<(String, Color)> = [(title.to_string(), Color::Magenta)]
+            .into_iter()
+            .chain(options.iter().map(|option| (option.clone(), Color::White)))
+            .collect();
+
+        self.draw_panel(&lines)
+    }
+
+    fn draw_summary(&mut self, title: &str, lines: &[String]) -> io::Result<()> {
+        let lines: Vec<(String, Color)> = [(title.to_string(), Color::Red)]
+            .into_iter()
+            .chain(lines.iter().map(|line| (line.clone(), Color::White)))
+            .chain([(String::from("Press any key"), Color::DarkGrey)])
+            .collect();
+
+        self.draw_panel(&lines)
+    }
+
+    // Takes over the bottom border, with the last message just above it
+    fn draw_console(&mut self, input: &str, message: &str) -> io::Result<()> {
+        let width = self.dimensions.x.saturating_sub(1) as usize;
+        let bottom = self.top_left + vector!(1, self.dimensions.y);
+        let prompt: String = format!("> {}_", input).chars().take(width).collect();
+        let message: String = message.chars().take(width).collect();
+
+        self.screen.put_str(
+            bottom - vector![0, 1],
+            &format!("{:<width$}", message, width = width),
+            Color::DarkGrey,
+            Color::Black,
+        );
+        self.screen.put_str(
+            bottom,
+            &format!("{:<width$}", prompt, width = width),
+            Color::Yellow,
+            Color::Black,
+        );
+        self.screen.present(self.stdout)
+    }
+
+    // Re-centers the frame and HUD, leaving a notice if the frame no longer fits
+    fn resize(&mut self, cols: u16, rows: u16) -> io::Result<bool> {
+        self.top_left = layout(Vector2::new(cols, rows), self.dimensions);
+        self.status_indicators = status_indicators(self.top_left, self.dimensions);
+        self.game_area_offset = self.top_left - Point2::new(0, 0) + Vector2::new(1, 1);
+        self.render_actions.clear();
+        self.screen = Screen::new(Vector2::new(cols, rows));
 
-        self.draw_state(state)?;
+        execute!(self.stdout, terminal::Clear(terminal::ClearType::All))?;
 
-        execute!(self.stdout, terminal::EndSynchronizedUpdate)?;
+        if fits(Vector2::new(cols, rows), self.dimensions) {
+            return Ok(true);
+        }
 
-        Ok(())
+        let needed = footprint(self.dimensions);
+        let lines = [
+            String::from("Terminal too small"),
+            format!("Need {}x{}, have {}x{}", needed.x, needed.y, cols, rows),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            self.screen
+                .put_str(Point2::new(0, i as u16), line, Color::Red, Color::Reset);
+        }
+        self.screen.present(self.stdout)?;
+        Ok(false)
     }
 }
 
-fn draw_value(
-    stdout: &mut io::Stdout,
-    indicator: Option<&Indicator>,
-    value: String,
-) -> io::Result<()> {
-    if indicator.is_none() {
-        return Ok(());
+fn draw_value(screen: &mut Screen, indicator: Option<&Indicator>, value: String) {
+    if let Some(ind) = indicator {
+        screen.put_str(ind.coord, &value, ind.color, ind.bg_color);
     }
+}
 
-    let ind = indicator.unwrap();
+const XP_BAR_WIDTH: u32 = 8;
 
-    execute!(
-        stdout,
-        cursor::MoveTo(ind.coord.x, ind.coord.y),
-        style::PrintStyledContent(value.with(ind.color).on(ind.bg_color)),
-    )?;
+fn xp_bar(xp: u32, next_level_xp: u32) -> String {
+    let filled = (xp * XP_BAR_WIDTH / next_level_xp.max(1)).min(XP_BAR_WIDTH);
+    (0..XP_BAR_WIDTH)
+        .map(|i| Glyphs::current().bar(i < filled))
+        .collect()
+}
 
-    Ok(())
+fn draw_inventory(screen: &mut Screen, indicator: Option<&Indicator>, player: &Player) {
+    let Some(ind) = indicator else {
+        return;
+    };
+
+    screen.put_str(ind.coord, "Items", ind.color, ind.bg_color);
+
+    for (i, item) in CONSUMABLES.iter().enumerate() {
+        let count = player.inventory.count(*item);
+        let spot = Point2::new(ind.coord.x, ind.coord.y + 1 + i as u16);
+
+        let x = screen.put_str(
+            spot,
+            &padded(item.as_symbol()),
+            item.as_color(),
+            Color::Reset,
+        );
+        screen.put_str(
+            Point2::new(x, spot.y),
+            &format!("x{:<2}", count),
+            if count > 0 {
+                ind.color
+            } else {
+                Color::DarkGrey
+            },
+            Color::Reset,
+        );
+    }
+
+    let gear_row = ind.coord.y + 2 + CONSUMABLES.len() as u16;
+    screen.put_str(
+        Point2::new(ind.coord.x, gear_row),
+        "Gear",
+        ind.color,
+        ind.bg_color,
+    );
+
+    for (i, gear) in player.equipment.equipped().enumerate() {
+        let item = Item::Gear(gear);
+        screen.put_str(
+            Point2::new(ind.coord.x, gear_row + 1 + i as u16),
+            &padded(item.as_symbol()),
+            item.as_color(),
+            Color::Reset,
+        );
+    }
+}
+
+fn resource_label(spell: &dyn Ability, cost: u32, ticker: u128) -> String {
+    match spell.resource() {
+        Resource::Energy => format!("{:0>2}", cost),
+        Resource::Charges(charges) => {
+            let available = charges.available(ticker);
+            (0..charges.max)
+                .map(|i| Glyphs::current().charge(i < available))
+                .collect()
+        }
+        Resource::Blood => format!("{}{:0>2}", Glyphs::current().heart(), cost),
+        Resource::Combo => format!("{}{:0>2}", Glyphs::current().combo(), cost),
+    }
 }
 
 fn draw_spells(
-    stdout: &mut io::Stdout,
+    screen: &mut Screen,
     indicator: Option<&Indicator>,
+    end: u16,
     player: &Player,
     ticker: u128,
-) -> io::Result<()> {
-    if indicator.is_none() {
-        return Ok(());
-    }
+) {
+    let Some(ind) = indicator else {
+        return;
+    };
 
-    let ind = indicator.unwrap();
-
-    execute!(stdout, cursor::MoveTo(ind.coord.x, ind.coord.y))?;
+    let y = ind.coord.y;
+    let mut x = ind.coord.x;
+    let glyphs = Glyphs::current();
+    let edge = glyphs.frame()[4];
 
     let spell_len = player.spells.len();
     for i in 0..spell_len {
         let spell = &player.spells[i];
         let is_active = i == player.active_spell;
+        let cooldown = player.spell_cooldown(spell.as_ref());
+        let remaining = player.spell_remaining_cooldown(spell.as_ref(), ticker);
 
-        let (color, bg_color) = match (is_active, player.energy >= spell.cost()) {
+        let (color, bg_color) = match (is_active, player.can_afford(spell.as_ref(), ticker)) {
             (true, true) => (Color::DarkMagenta, ind.color),
             (true, false) => (Color::DarkGrey, ind.color),
             (false, true) => (ind.color, ind.bg_color),
             (false, false) => (Color::Grey, ind.bg_color),
         };
 
+        let label = resource_label(spell.as_ref(), player.spell_cost(spell.as_ref()), ticker);
+        // Leading edge, symbol, cooldown, label and trailing edge
+        let width = u16::from(i > 0) + 2 + 1 + label.width() as u16 + 1;
+        if x + width > end {
+            break;
+        }
+
         if i > 0 {
-            execute!(
-                stdout,
-                style::PrintStyledContent("═".with(ind.bg_color).on(Color::Black))
-            )?;
+            x = screen.put(Point2::new(x, y), edge, ind.bg_color, Color::Black);
         }
-        execute!(
-            stdout,
-            style::PrintStyledContent(
-                spell
-                    .get_spell()
-                    .as_symbol()
-                    .with(spell.get_spell().as_color())
-                    .on(bg_color)
-            ),
-            style::PrintStyledContent(
-                format!(
-                    "{}",
-                    loader_reverse(
-                        spell
-                            .cooldown()
-                            .saturating_sub(spell.remaining_cooldown(ticker)),
-                        spell.cooldown(),
-                        spell.cooldown()
-                    ),
-                )
-                .with(spell.get_spell().as_color())
-                .on(bg_color)
-            ),
-            style::PrintStyledContent(format!("{:0>2}", spell.cost()).with(color).on(bg_color)),
-            style::PrintStyledContent("═".with(ind.bg_color).on(Color::Black))
-        )?;
+        let spell_color = spell.get_spell().as_color();
+        x = screen.put_str(
+            Point2::new(x, y),
+            &padded(spell.get_spell().as_symbol()),
+            spell_color,
+            bg_color,
+        );
+        x = screen.put(
+            Point2::new(x, y),
+            loader_reverse(cooldown.saturating_sub(remaining), cooldown, cooldown),
+            spell_color,
+            bg_color,
+        );
+        x = screen.put_str(Point2::new(x, y), &label, color, bg_color);
+        // The secondary spell is marked in its trailing border
+        x = if i == player.secondary_spell {
+            screen.put(
+                Point2::new(x, y),
+                glyphs.secondary(),
+                Color::DarkCyan,
+                Color::Black,
+            )
+        } else {
+            screen.put(Point2::new(x, y), edge, ind.bg_color, Color::Black)
+        };
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MIN_GAME_BOUNDS;
+
+    use super::*;
+
+    #[test]
+    fn layout_centers_frame_and_inventory() {
+        let dimensions = vector![60, 20];
+        let needed = footprint(dimensions);
+        assert_eq!(needed, vector![67, 21]);
 
-    Ok(())
+        let top_left = layout(vector![100, 40], dimensions);
+        assert_eq!(top_left.x, (100 - needed.x) / 2);
+        assert_eq!(top_left.y, (40 - needed.y) / 3);
+        // The inventory beside the frame stays on screen
+        assert!(top_left.x + needed.x <= 100);
+
+        assert_eq!(layout(needed, dimensions), Point2::new(0, 0));
+        assert_eq!(layout(vector![10, 5], dimensions), Point2::new(0, 0));
+    }
+
+    #[test]
+    fn too_small_counts_the_inventory() {
+        let dimensions = vector![60, 20];
+        assert!(fits(vector![67, 21], dimensions));
+        assert!(fits(vector![100, 40], dimensions));
+        assert!(!fits(vector![66, 21], dimensions));
+        assert!(!fits(vector![67, 20], dimensions));
+    }
+
+    const LEVEL_WIDTH: u16 = 5 + XP_BAR_WIDTH as u16;
+
+    #[test]
+    fn indicators_fit_the_smallest_frame() {
+        // As `game()` sizes the frame for the arena
+        let dimensions = vector![2 * (MIN_GAME_BOUNDS.x + 1) + 1, MIN_GAME_BOUNDS.y + 2];
+        let top_left = Point2::new(0, 0);
+        let indicators = status_indicators(top_left, dimensions);
+        let span = |name: &str, width: u16| {
+            let x = indicators[name].coord.x;
+            (x, x + width)
+        };
+
+        let top = [
+            span("score", SCORE_WIDTH),
+            span("floor", FLOOR_WIDTH),
+            span("level", LEVEL_WIDTH),
+            span("combo", COMBO_WIDTH),
+            span("clock", CLOCK_WIDTH),
+        ];
+        let bottom = [span("health", HEALTH_WIDTH), span("energy", ENERGY_WIDTH)];
+        for row in [&top[..], &bottom[..]] {
+            for pair in row.windows(2) {
+                assert!(pair[0].1 < pair[1].0, "{:?}", pair);
+            }
+            assert!(row.last().unwrap().1 < dimensions.x);
+        }
+
+        // Room for at least two spells before health
+        assert!(indicators["health"].coord.x - indicators["spells"].coord.x > 2 * 9);
+    }
 }
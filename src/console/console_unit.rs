@@ -1,8 +1,14 @@
 use crossterm::style::Color;
 
-use crate::{monster::Monster, object::Object, player::Player};
+use crate::{
+    ally::Ally,
+    item::Pickup,
+    monster::{Archetype, Monster},
+    object::Object,
+    player::Player,
+};
 
-use super::{AsColor, AsSymbol};
+use super::{AsColor, AsSymbol, Glyphs};
 
 pub trait ConsoleUnit {
     fn color(&self) -> Color;
@@ -15,21 +21,36 @@ impl ConsoleUnit for Player {
     }
 
     fn symbol(&self) -> char {
-        '🧙'
+        self.class.as_symbol()
     }
 }
 
-const SYMBOLS: [char; 84] = [
-    '🦇', '🪰', '🦟', '🐢', '🐈', '🐲', '🦍', '🦬', '🦌', '🦏', '🦛', '🐂', '🐃', '🐄', '🐖', '🐏',
-    '🐑', '🐐', '🐪', '🐫', '🦙', '🦘', '🦥', '🦨', '🦡', '🐘', '🦣', '🐁', '🐀', '🦔', '🐇', '🦫',
-    '🐉', '🦎', '🐊', '🐢', '🐍', '🦕', '🦖', '🦦', '🦈', '🐬', '🦭', '🐋', '🐟', '🐠', '🐡', '🦐',
-    '🦑', '🐙', '🦞', '🦀', '🦆', '🐓', '🪼', '🦃', '🦅', '🦢', '🦜', '🪿', '🦩', '🦚', '🦉', '🦤',
-    '🐦', '🐧', '🐥', '🐤', '🦋', '🐌', '🐛', '🪱', '🦗', '🐜', '🪳', '🐝', '🪲', '🐞', '🦂', '🦠',
-    '🧞', '🧟', '🧌', '🫏',
+const SYMBOLS: [char; 81] = [
+    '🦇', '🪰', '🦟', '🐢', '🐈', '🦍', '🦬', '🦌', '🦏', '🦛', '🐂', '🐃', '🐄', '🐖', '🐏', '🐑',
+    '🐐', '🐪', '🐫', '🦙', '🦘', '🦥', '🦨', '🦡', '🐘', '🦣', '🐁', '🐀', '🦔', '🐇', '🦫', '🦎',
+    '🐊', '🐢', '🐍', '🦕', '🦖', '🦦', '🦈', '🐬', '🦭', '🐋', '🐟', '🐠', '🐡', '🦐', '🦑', '🐙',
+    '🦞', '🦀', '🦆', '🐓', '🪼', '🦃', '🦅', '🦢', '🦜', '🪿', '🦩', '🦚', '🦉', '🦤', '🐦', '🐧',
+    '🐥', '🐤', '🦋', '🐌', '🐛', '🪱', '🦗', '🐜', '🪳', '🐝', '🪲', '🐞', '🦂', '🦠', '🧞', '🧟',
+    '🫏',
+];
+
+const GREEK: [char; 24] = [
+    'α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ', 'ι', 'κ', 'λ', 'μ', 'ν', 'ξ', 'π', 'ρ', 'σ', 'τ', 'υ',
+    'φ', 'χ', 'ψ', 'ω', 'Ж',
+];
+
+const LETTERS: [char; 47] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'p', 'q', 'r', 's', 't',
+    'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N',
+    'O', 'P', 'Q', 'R', 'S', 'U', 'V', 'Y', 'Z',
 ];
 
 impl ConsoleUnit for Monster {
     fn color(&self) -> Color {
+        if let Some(aura) = self.aura {
+            return aura.element.as_color();
+        }
+
         match self.id % 11 {
             0 => Color::Grey,
             1 => Color::DarkGrey,
@@ -47,8 +68,48 @@ impl ConsoleUnit for Monster {
     }
 
     fn symbol(&self) -> char {
-        // let symbols = ['W', 'X', 'Y'];
-        SYMBOLS[(self.id % SYMBOLS.len() as u64) as usize]
+        monster_glyph(self.archetype, self.id, Glyphs::current())
+    }
+}
+
+fn monster_glyph(archetype: Archetype, id: u64, glyphs: Glyphs) -> char {
+    match archetype {
+        Archetype::Troll => return glyphs.troll(),
+        Archetype::Dragon => return glyphs.dragon(),
+        _ => {}
+    }
+
+    let symbols = monster_symbols(glyphs);
+    symbols[(id % symbols.len() as u64) as usize]
+}
+
+// Every other glyph in the theme is left out so monsters can't be mistaken
+// for spells, items or the player
+fn monster_symbols(glyphs: Glyphs) -> &'static [char] {
+    match glyphs {
+        Glyphs::Emoji => &SYMBOLS,
+        Glyphs::Unicode => &GREEK,
+        Glyphs::Ascii => &LETTERS,
+    }
+}
+
+impl ConsoleUnit for Ally {
+    fn color(&self) -> Color {
+        self.spell.as_color()
+    }
+
+    fn symbol(&self) -> char {
+        self.spell.as_symbol()
+    }
+}
+
+impl ConsoleUnit for Pickup {
+    fn color(&self) -> Color {
+        self.item.as_color()
+    }
+
+    fn symbol(&self) -> char {
+        self.item.as_symbol()
     }
 }
 
@@ -71,3 +132,47 @@ impl ConsoleUnit for &dyn Object {
         self.get_spell().as_symbol()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{
+        class::CLASSES,
+        equipment::GEAR,
+        item::{Item, CONSUMABLES},
+        magic::Spell,
+    };
+
+    #[test]
+    fn every_glyph_is_unique() {
+        let spells = [
+            Spell::Fireball,
+            Spell::Sphere,
+            Spell::Inferno,
+            Spell::Spark,
+            Spell::Orb,
+            Spell::Steam,
+            Spell::Arrow,
+            Spell::Sweep,
+            Spell::Summon,
+            Spell::Venom,
+        ];
+
+        for glyphs in [Glyphs::Emoji, Glyphs::Unicode, Glyphs::Ascii] {
+            let mut reserved: Vec<char> = spells.iter().map(|spell| spell.glyph(glyphs)).collect();
+            reserved.extend(CONSUMABLES.iter().map(|item| item.glyph(glyphs)));
+            reserved.extend(GEAR.map(|gear| Item::Gear(gear).glyph(glyphs)));
+            reserved.push(CLASSES[0].glyph(glyphs));
+            reserved.extend([glyphs.door(), glyphs.troll(), glyphs.dragon()]);
+            reserved.extend(glyphs.death());
+
+            let unique: HashSet<char> = reserved.iter().copied().collect();
+            assert_eq!(unique.len(), reserved.len(), "{:?}", glyphs);
+            for symbol in monster_symbols(glyphs) {
+                assert!(!unique.contains(symbol), "{:?} {}", glyphs, symbol);
+            }
+        }
+    }
+}
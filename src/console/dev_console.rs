@@ -0,0 +1,81 @@
+use std::io;
+
+use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyEventKind};
+
+use crate::{command::Command, dev_command, display::Display};
+
+use super::Keymap;
+
+// Input line for developer commands, remembering what was typed before
+pub struct DevConsole {
+    history: Vec<String>,
+}
+
+impl DevConsole {
+    pub fn new() -> Self {
+        Self {
+            history: Vec::new(),
+        }
+    }
+
+    // The next line entered, None once the console is closed
+    pub fn read_line(
+        &mut self,
+        display: &mut impl Display,
+        keymap: &Keymap,
+        message: &str,
+    ) -> io::Result<Option<String>> {
+        let mut input = String::new();
+        let mut message = message.to_string();
+        let mut browsing = self.history.len();
+
+        loop {
+            display.draw_console(&input, &message)?;
+
+            let event = read()?;
+            if keymap.system_command(&event) == Some(Command::DevConsole) {
+                return Ok(None);
+            }
+            let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            }) = event
+            else {
+                continue;
+            };
+
+            match code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter => {
+                    let line = input.trim().to_string();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if self.history.last() != Some(&line) {
+                        self.history.push(line.clone());
+                    }
+                    return Ok(Some(line));
+                }
+                KeyCode::Tab => {
+                    let (completed, matches) = dev_command::complete(&input);
+                    input = completed;
+                    message = matches.join(" ");
+                }
+                KeyCode::Up if browsing > 0 => {
+                    browsing -= 1;
+                    input = self.history[browsing].clone();
+                }
+                KeyCode::Down if browsing < self.history.len() => {
+                    browsing += 1;
+                    input = self.history.get(browsing).cloned().unwrap_or_default();
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    }
+}
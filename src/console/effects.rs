@@ -0,0 +1,138 @@
+use crossterm::style::Color;
+use nalgebra::Point2;
+
+use crate::render_action::Effect;
+
+use super::Glyphs;
+
+// A step of an effect, None keeps what is underneath
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EffectFrame {
+    pub symbol: Option<char>,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    // Covers the four neighbouring cells as well
+    pub spread: bool,
+}
+
+// Where an effect is drawn, monsters are looked up again every frame
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Anchor {
+    Spot(Point2<f64>),
+    Monster(u64),
+}
+
+pub struct ActiveEffect {
+    pub effect: Effect,
+    pub color: Color,
+    pub anchor: Anchor,
+    pub start: u128,
+}
+
+fn duration(effect: Effect) -> u128 {
+    match effect {
+        Effect::Explosion => 300,
+        Effect::Hit => 150,
+        Effect::Trail => 150,
+        Effect::DeathPuff => 400,
+        Effect::HurtFlash => 200,
+    }
+}
+
+fn glyph(symbol: char, fg: Color, spread: bool) -> EffectFrame {
+    EffectFrame {
+        symbol: Some(symbol),
+        fg: Some(fg),
+        bg: None,
+        spread,
+    }
+}
+
+fn tint(fg: Option<Color>, bg: Option<Color>) -> EffectFrame {
+    EffectFrame {
+        symbol: None,
+        fg,
+        bg,
+        spread: false,
+    }
+}
+
+impl ActiveEffect {
+    pub fn expired(&self, ticker: u128) -> bool {
+        ticker.saturating_sub(self.start) >= duration(self.effect)
+    }
+
+    fn frames(&self) -> Vec<EffectFrame> {
+        let glyphs = Glyphs::current();
+        match self.effect {
+            Effect::Explosion => {
+                let [burst, flame, smoke] = match glyphs {
+                    Glyphs::Emoji => ['💥', '🔥', '💨'],
+                    Glyphs::Unicode => ['✸', '✶', '·'],
+                    Glyphs::Ascii => ['*', '+', '.'],
+                };
+                vec![
+                    glyph(burst, self.color, false),
+                    glyph(flame, Color::Yellow, true),
+                    glyph(smoke, Color::DarkGrey, true),
+                ]
+            }
+            Effect::Hit => vec![tint(None, Some(Color::White)), tint(None, Some(Color::Red))],
+            Effect::Trail => {
+                let dot = match glyphs {
+                    Glyphs::Ascii => '.',
+                    _ => '·',
+                };
+                vec![
+                    glyph(dot, self.color, false),
+                    glyph(dot, Color::DarkGrey, false),
+                ]
+            }
+            Effect::DeathPuff => {
+                let [puff, cloud, wisp] = match glyphs {
+                    Glyphs::Emoji => ['💨', '☁', '·'],
+                    Glyphs::Unicode => ['※', '∴', '·'],
+                    Glyphs::Ascii => ['%', ':', '.'],
+                };
+                vec![
+                    glyph(puff, Color::Grey, false),
+                    glyph(cloud, Color::Grey, false),
+                    glyph(wisp, Color::DarkGrey, false),
+                ]
+            }
+            Effect::HurtFlash => vec![
+                tint(Some(Color::Red), None),
+                tint(Some(Color::DarkRed), None),
+            ],
+        }
+    }
+
+    // Frames are spread evenly over the duration
+    pub fn frame(&self, ticker: u128) -> EffectFrame {
+        let frames = self.frames();
+        let elapsed = ticker.saturating_sub(self.start);
+        let ix = (elapsed * frames.len() as u128 / duration(self.effect)) as usize;
+        frames[ix.min(frames.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_advance_until_the_effect_expires() {
+        let effect = ActiveEffect {
+            effect: Effect::Explosion,
+            color: Color::Red,
+            anchor: Anchor::Spot(Point2::new(3., 3.)),
+            start: 1000,
+        };
+        assert_eq!(effect.frame(1000).fg, Some(Color::Red));
+        assert!(!effect.frame(1000).spread);
+        assert_eq!(effect.frame(1150).fg, Some(Color::Yellow));
+        assert_eq!(effect.frame(1299).fg, Some(Color::DarkGrey));
+        assert!(!effect.expired(1299));
+        assert!(effect.expired(1300));
+    }
+}
@@ -0,0 +1,166 @@
+use std::{env, str::FromStr, sync::OnceLock};
+
+use unicode_width::UnicodeWidthChar;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Glyphs {
+    Emoji,
+    // Single width symbols for fonts without emoji
+    Unicode,
+    // Letters and punctuation, like the classic roguelikes
+    Ascii,
+}
+
+static GLYPHS: OnceLock<Glyphs> = OnceLock::new();
+
+impl FromStr for Glyphs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "emoji" => Ok(Glyphs::Emoji),
+            "unicode" => Ok(Glyphs::Unicode),
+            "ascii" => Ok(Glyphs::Ascii),
+            _ => Err(format!(
+                "Unknown glyphs: {}, expected emoji, unicode or ascii",
+                s
+            )),
+        }
+    }
+}
+
+impl Glyphs {
+    // The Linux console has no emoji and few symbols
+    pub fn detect() -> Self {
+        match env::var("TERM") {
+            Ok(term) if term == "linux" => Glyphs::Ascii,
+            _ => Glyphs::Emoji,
+        }
+    }
+
+    // Set once at startup, before anything is drawn
+    pub fn install(self) {
+        let _ = GLYPHS.set(self);
+    }
+
+    pub fn current() -> Self {
+        GLYPHS.get().copied().unwrap_or(Glyphs::Emoji)
+    }
+
+    pub fn door(self) -> char {
+        match self {
+            Glyphs::Emoji => '🚪',
+            Glyphs::Unicode => '∏',
+            Glyphs::Ascii => '+',
+        }
+    }
+
+    pub fn troll(self) -> char {
+        match self {
+            Glyphs::Emoji => '🧌',
+            Glyphs::Unicode => 'Ψ',
+            Glyphs::Ascii => 'T',
+        }
+    }
+
+    pub fn dragon(self) -> char {
+        match self {
+            Glyphs::Emoji => '🐉',
+            Glyphs::Unicode => 'Δ',
+            Glyphs::Ascii => 'D',
+        }
+    }
+
+    pub fn death(self) -> [char; 3] {
+        match self {
+            Glyphs::Emoji => ['😵', '💀', '🪦'],
+            Glyphs::Unicode => ['☹', '☠', '✝'],
+            Glyphs::Ascii => ['X', '%', '&'],
+        }
+    }
+
+    // Corners top left, top right, bottom left, bottom right, then the
+    // horizontal and vertical edges
+    pub fn frame(self) -> [char; 6] {
+        match self {
+            Glyphs::Emoji | Glyphs::Unicode => ['╔', '╗', '╚', '╝', '═', '║'],
+            Glyphs::Ascii => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+
+    pub fn heart(self) -> char {
+        match self {
+            Glyphs::Emoji | Glyphs::Unicode => '♥',
+            Glyphs::Ascii => 'H',
+        }
+    }
+
+    pub fn combo(self) -> char {
+        match self {
+            Glyphs::Emoji | Glyphs::Unicode => '✦',
+            Glyphs::Ascii => '*',
+        }
+    }
+
+    pub fn secondary(self) -> char {
+        match self {
+            Glyphs::Emoji | Glyphs::Unicode => '◆',
+            Glyphs::Ascii => '#',
+        }
+    }
+
+    pub fn bar(self, filled: bool) -> char {
+        match (self, filled) {
+            (Glyphs::Ascii, true) => '#',
+            (Glyphs::Ascii, false) => '-',
+            (_, true) => '▰',
+            (_, false) => '▱',
+        }
+    }
+
+    pub fn charge(self, available: bool) -> char {
+        match (self, available) {
+            (Glyphs::Ascii, true) => 'o',
+            (Glyphs::Ascii, false) => '.',
+            (_, true) => '●',
+            (_, false) => '○',
+        }
+    }
+
+    pub fn loading(self) -> [char; 9] {
+        match self {
+            Glyphs::Emoji | Glyphs::Unicode => [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+            Glyphs::Ascii => [' ', '.', ':', '-', '=', '+', '*', '#', '@'],
+        }
+    }
+}
+
+// Narrow glyphs take a trailing space so the HUD lines up whatever the theme
+pub fn padded(symbol: char) -> String {
+    match symbol.width() {
+        Some(2) => symbol.to_string(),
+        _ => format!("{} ", symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use unicode_width::UnicodeWidthStr;
+
+    use super::*;
+
+    #[test]
+    fn fallback_glyphs_are_single_width() {
+        for glyphs in [Glyphs::Unicode, Glyphs::Ascii] {
+            let symbols: String = [glyphs.door(), glyphs.heart(), glyphs.combo()]
+                .into_iter()
+                .chain(glyphs.death())
+                .chain(glyphs.frame())
+                .chain(glyphs.loading())
+                .collect();
+            assert_eq!(symbols.width(), symbols.chars().count(), "{:?}", glyphs);
+        }
+        assert_eq!(padded('@').width(), 2);
+        assert_eq!(padded('🚪').width(), 2);
+    }
+}
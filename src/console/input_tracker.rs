@@ -1,15 +1,38 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
 
-use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
 use nalgebra::{convert, Point2, Scale2, Vector2};
 
 use super::input::*;
 
+// Terminals without the kitty keyboard protocol only send presses, repeated
+// while a key is held, so releases have to be guessed from their absence
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyReleases {
+    Reported,
+    Synthesized,
+}
+
+// Long enough to cover the delay before auto-repeat kicks in
+const FIRST_REPEAT_TIMEOUT: Duration = Duration::from_millis(500);
+const REPEAT_TIMEOUT: Duration = Duration::from_millis(150);
+
+struct Hold {
+    last_seen: Instant,
+    repeating: bool,
+}
+
 pub struct InputTracker {
+    key_releases: KeyReleases,
     pressed_keys: HashSet<KeyCode>,
+    holds: HashMap<KeyCode, Hold>,
     pressed_mouse_buttons: HashSet<MouseButton>,
     mouse_coord: Point2<u16>,
-    current_events: VecDeque<Event>,
+    modifiers: KeyModifiers,
+    current_events: VecDeque<(Event, Instant)>,
     current_state: HashSet<InputState>,
     current_mouse_coord: Point2<f64>,
     game_area_offset: Vector2<f64>,
@@ -19,18 +42,27 @@ pub struct InputTracker {
 impl InputTracker {
     #[allow(dead_code)]
     pub fn new() -> Self {
-        Self::new_mouse(Point2::new(0, 0), Vector2::new(1., 1.), Scale2::new(2., 1.))
+        Self::new_mouse(
+            Point2::new(0, 0),
+            Vector2::new(1., 1.),
+            Scale2::new(2., 1.),
+            KeyReleases::Reported,
+        )
     }
 
     pub fn new_mouse(
         mouse_location: Point2<u16>,
         game_area_offset: Vector2<f64>,
         game_area_scale: Scale2<f64>,
+        key_releases: KeyReleases,
     ) -> Self {
         Self {
+            key_releases,
             pressed_keys: HashSet::new(),
+            holds: HashMap::new(),
             pressed_mouse_buttons: HashSet::new(),
             mouse_coord: mouse_location,
+            modifiers: KeyModifiers::empty(),
             current_events: VecDeque::new(),
             current_state: HashSet::new(),
             current_mouse_coord: convert(mouse_location),
@@ -40,39 +72,79 @@ impl InputTracker {
     }
 
     pub fn register_input_event(&mut self, event: Event) {
+        self.register_input_event_at(event, Instant::now());
+    }
+
+    fn register_input_event_at(&mut self, event: Event, now: Instant) {
         match event {
             Event::Key(_) | Event::Mouse(_) => {
-                self.current_events.push_back(event);
+                self.current_events.push_back((event, now));
             }
             _ => {}
         }
     }
 
-    pub fn calculate_state(&mut self) -> (&HashSet<InputState>, &Point2<f64>) {
+    pub fn reset(&mut self) {
+        self.pressed_keys.clear();
+        self.holds.clear();
+        self.pressed_mouse_buttons.clear();
+        self.modifiers = KeyModifiers::empty();
+        self.current_events.clear();
+        self.current_state.clear();
+    }
+
+    // The game area moves when the terminal is resized
+    pub fn set_game_area_offset(&mut self, game_area_offset: Vector2<f64>) {
+        self.game_area_offset = game_area_offset;
+    }
+
+    // Inputs this tick, the cursor in game coordinates and the modifiers held
+    pub fn calculate_state(&mut self) -> (&HashSet<InputState>, &Point2<f64>, KeyModifiers) {
+        self.calculate_state_at(Instant::now())
+    }
+
+    fn calculate_state_at(
+        &mut self,
+        now: Instant,
+    ) -> (&HashSet<InputState>, &Point2<f64>, KeyModifiers) {
         let mut new_state: HashSet<InputState> = HashSet::new();
         let mut still_active_keys = self.pressed_keys.clone();
         let mut still_active_mouse = self.pressed_mouse_buttons.clone();
 
-        while let Some(event) = self.current_events.pop_front() {
+        while let Some((event, seen)) = self.current_events.pop_front() {
             match event {
-                Event::Key(key_event) => match key_event.kind {
-                    KeyEventKind::Press => {
-                        if !self.pressed_keys.contains(&key_event.code) {
-                            new_state.insert(InputState::Press(Input::Key(key_event.code)));
-                            new_state.insert(InputState::Active(Input::Key(key_event.code)));
-                            self.pressed_keys.insert(key_event.code);
+                Event::Key(key_event) => {
+                    self.modifiers = key_event.modifiers;
+                    let code = normalize(key_event.code);
+                    match key_event.kind {
+                        KeyEventKind::Press | KeyEventKind::Repeat => {
+                            if !self.pressed_keys.contains(&code) {
+                                new_state.insert(InputState::Press(Input::Key(code)));
+                                new_state.insert(InputState::Active(Input::Key(code)));
+                                self.pressed_keys.insert(code);
+                                self.holds.insert(
+                                    code,
+                                    Hold {
+                                        last_seen: seen,
+                                        repeating: false,
+                                    },
+                                );
+                            } else if let Some(hold) = self.holds.get_mut(&code) {
+                                hold.last_seen = seen;
+                                hold.repeating = true;
+                            }
+                        }
+                        KeyEventKind::Release => {
+                            new_state.insert(InputState::Release(Input::Key(code)));
+                            self.pressed_keys.remove(&code);
+                            self.holds.remove(&code);
+                            still_active_keys.remove(&code);
                         }
                     }
-                    KeyEventKind::Release => {
-                        new_state.insert(InputState::Release(Input::Key(key_event.code)));
-                        self.pressed_keys.remove(&key_event.code);
-                        still_active_keys.remove(&key_event.code);
-                    }
-                    KeyEventKind::Repeat => todo!(),
-                },
+                }
                 Event::Mouse(mouse_event) => {
-                    self.mouse_coord =
-                        Point2::new(mouse_event.column.into(), mouse_event.row.into());
+                    self.modifiers = mouse_event.modifiers;
+                    self.mouse_coord = Point2::new(mouse_event.column, mouse_event.row);
 
                     if let Some(input) = mouse_event.kind.as_input() {
                         match mouse_event.kind {
@@ -102,6 +174,23 @@ impl InputTracker {
             }
         }
 
+        if self.key_releases == KeyReleases::Synthesized {
+            for (code, hold) in &self.holds {
+                let timeout = if hold.repeating {
+                    REPEAT_TIMEOUT
+                } else {
+                    FIRST_REPEAT_TIMEOUT
+                };
+                if now.duration_since(hold.last_seen) > timeout {
+                    new_state.insert(InputState::Release(Input::Key(*code)));
+                    self.pressed_keys.remove(code);
+                    still_active_keys.remove(code);
+                }
+            }
+            self.holds
+                .retain(|code, _| self.pressed_keys.contains(code));
+        }
+
         for code in still_active_keys {
             new_state.insert(InputState::Active(Input::Key(code)));
         }
@@ -120,7 +209,19 @@ impl InputTracker {
             .try_inverse_transform_point(&offset_coord)
             .unwrap();
 
-        (&self.current_state, &self.current_mouse_coord)
+        (
+            &self.current_state,
+            &self.current_mouse_coord,
+            self.modifiers,
+        )
+    }
+}
+
+// Shift changes the case of a held letter, it is still the same key
+fn normalize(code: KeyCode) -> KeyCode {
+    match code {
+        KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+        _ => code,
     }
 }
 
@@ -154,7 +255,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 2);
@@ -163,7 +264,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_release_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
@@ -178,7 +279,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 2);
@@ -186,7 +287,7 @@ mod tests {
         assert!(state.contains(&InputState::Active(Input::Key(code))));
 
         // Act
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
@@ -194,7 +295,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_release_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
@@ -210,7 +311,7 @@ mod tests {
         // Act
         tracker.register_input_event(key_press_event(code));
         tracker.register_input_event(key_release_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 3);
@@ -219,7 +320,7 @@ mod tests {
         assert!(state.contains(&InputState::Release(Input::Key(code))));
 
         // Act
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 0);
@@ -233,7 +334,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 2);
@@ -243,7 +344,7 @@ mod tests {
         // Act
         tracker.register_input_event(key_release_event(code));
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 3);
@@ -253,7 +354,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_release_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
@@ -268,7 +369,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 2);
@@ -278,14 +379,14 @@ mod tests {
         // Act
         tracker.register_input_event(key_press_event(code));
         tracker.register_input_event(key_release_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
         assert!(state.contains(&InputState::Release(Input::Key(code))));
 
         // Act
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 0);
@@ -299,7 +400,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 2);
@@ -310,7 +411,7 @@ mod tests {
         tracker.register_input_event(key_press_event(code));
         tracker.register_input_event(key_release_event(code));
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 3);
@@ -319,7 +420,7 @@ mod tests {
         assert!(state.contains(&InputState::Release(Input::Key(code))));
 
         // Act
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
@@ -334,7 +435,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 2);
@@ -343,7 +444,7 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_press_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
 
         // Assert
         assert_eq!(state.len(), 1);
@@ -351,7 +452,63 @@ mod tests {
 
         // Act
         tracker.register_input_event(key_release_event(code));
-        let (state, _) = tracker.calculate_state();
+        let (state, _, _) = tracker.calculate_state();
+
+        // Assert
+        assert_eq!(state.len(), 1);
+        assert!(state.contains(&InputState::Release(Input::Key(code))));
+    }
+
+    #[test]
+    fn test_repeat_keeps_key_active() {
+        // Assign
+        let mut tracker = InputTracker::new();
+        let code = KeyCode::Char('b');
+        let repeat = Event::Key(KeyEvent::new_with_kind(
+            code,
+            KeyModifiers::empty(),
+            KeyEventKind::Repeat,
+        ));
+
+        // Act
+        tracker.register_input_event(key_press_event(code));
+        tracker.calculate_state();
+        tracker.register_input_event(repeat);
+        let (state, _, _) = tracker.calculate_state();
+
+        // Assert
+        assert_eq!(state.len(), 1);
+        assert!(state.contains(&InputState::Active(Input::Key(code))));
+    }
+
+    #[test]
+    fn test_synthesized_release() {
+        // Assign
+        let mut tracker = InputTracker::new();
+        tracker.key_releases = KeyReleases::Synthesized;
+        let code = KeyCode::Char('b');
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // Act
+        tracker.register_input_event_at(key_press_event(code), at(0));
+        tracker.calculate_state_at(at(0));
+        let (state, _, _) = tracker.calculate_state_at(at(400));
+
+        // Assert
+        assert!(state.contains(&InputState::Active(Input::Key(code))));
+
+        // Act
+        tracker.register_input_event_at(key_press_event(code), at(450));
+        tracker.register_input_event_at(key_press_event(code), at(480));
+        let (state, _, _) = tracker.calculate_state_at(at(600));
+
+        // Assert
+        assert_eq!(state.len(), 1);
+        assert!(state.contains(&InputState::Active(Input::Key(code))));
+
+        // Act
+        let (state, _, _) = tracker.calculate_state_at(at(700));
 
         // Assert
         assert_eq!(state.len(), 1);
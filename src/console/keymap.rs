@@ -0,0 +1,559 @@
+use std::{collections::HashMap, fmt, fs, io, str::FromStr};
+
+use crossterm::event::{
+    read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEventKind,
+};
+use log::warn;
+
+use crate::{
+    command::{Command, COMMANDS, GROUPS},
+    direction::Direction,
+    display::Display,
+    item::Item,
+    menu,
+};
+
+use super::input::{AsInput, Input, InputState};
+
+const KEYMAP_FILE: &str = "rust_dungeon.keys.toml";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Layout {
+    Qwerty,
+    Azerty,
+    Dvorak,
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "qwerty" => Ok(Layout::Qwerty),
+            "azerty" => Ok(Layout::Azerty),
+            "dvorak" => Ok(Layout::Dvorak),
+            _ => Err(format!("Unknown layout: {}", s)),
+        }
+    }
+}
+
+// An input together with the modifiers held when it happened. Shift is
+// ignored for character keys as it already decides which character is typed
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Chord {
+    pub input: Input,
+    pub modifiers: KeyModifiers,
+}
+
+impl Chord {
+    pub fn new(input: Input, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let input = match input {
+            Input::Key(KeyCode::Char(c)) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                Input::Key(KeyCode::Char(c.to_ascii_lowercase()))
+            }
+            _ => input,
+        };
+        Self { input, modifiers }
+    }
+
+    // The chord started by a key press, mouse click or scroll
+    pub fn pressed(event: &Event) -> Option<Self> {
+        match event {
+            Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) => Some(Chord::new(Input::Key(*code), *modifiers)),
+            Event::Mouse(mouse_event) => match mouse_event.kind {
+                MouseEventKind::Down(_)
+                | MouseEventKind::ScrollDown
+                | MouseEventKind::ScrollUp
+                | MouseEventKind::ScrollLeft
+                | MouseEventKind::ScrollRight => Some(Chord::new(
+                    mouse_event.kind.as_input()?,
+                    mouse_event.modifiers,
+                )),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+const MODIFIER_NAMES: [(KeyModifiers, &str); 3] = [
+    (KeyModifiers::CONTROL, "ctrl"),
+    (KeyModifiers::ALT, "alt"),
+    (KeyModifiers::SHIFT, "shift"),
+];
+
+const INPUT_NAMES: [(Input, &str); 23] = [
+    (Input::Key(KeyCode::Up), "up"),
+    (Input::Key(KeyCode::Down), "down"),
+    (Input::Key(KeyCode::Left), "left"),
+    (Input::Key(KeyCode::Right), "right"),
+    (Input::Key(KeyCode::Esc), "esc"),
+    (Input::Key(KeyCode::Enter), "enter"),
+    (Input::Key(KeyCode::Char(' ')), "space"),
+    (Input::Key(KeyCode::Tab), "tab"),
+    (Input::Key(KeyCode::Backspace), "backspace"),
+    (Input::Key(KeyCode::Delete), "delete"),
+    (Input::Key(KeyCode::Insert), "insert"),
+    (Input::Key(KeyCode::Home), "home"),
+    (Input::Key(KeyCode::End), "end"),
+    (Input::Key(KeyCode::PageUp), "pageup"),
+    (Input::Key(KeyCode::PageDown), "pagedown"),
+    (Input::MouseLeft, "mouse_left"),
+    (Input::MouseRight, "mouse_right"),
+    (Input::MouseMiddle, "mouse_middle"),
+    (Input::MouseScrollUp, "scroll_up"),
+    (Input::MouseScrollDown, "scroll_down"),
+    (Input::MouseScrollLeft, "scroll_left"),
+    (Input::MouseScrollRight, "scroll_right"),
+    (Input::Key(KeyCode::Char('+')), "plus"),
+];
+
+impl FromStr for Chord {
+    type Err = String;
+
+    // Modifiers joined by '+' before the key, e.g. "ctrl+shift+up"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').collect();
+        let key = parts
+            .pop()
+            .filter(|key| !key.is_empty())
+            .ok_or(format!("Missing key in: {}", s))?;
+
+        let mut modifiers = KeyModifiers::empty();
+        for part in parts {
+            let (modifier, _) = MODIFIER_NAMES
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(part))
+                .ok_or(format!("Unknown modifier: {}", part))?;
+            modifiers |= *modifier;
+        }
+
+        let input = if let Some((input, _)) = INPUT_NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(key))
+        {
+            *input
+        } else if let Some(n) = key
+            .strip_prefix(['f', 'F'])
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|n| (1..=12).contains(n))
+        {
+            Input::Key(KeyCode::F(n))
+        } else {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Input::Key(KeyCode::Char(c)),
+                _ => return Err(format!("Unknown key: {}", key)),
+            }
+        };
+
+        Ok(Chord::new(input, modifiers))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in MODIFIER_NAMES {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+
+        if let Some((_, name)) = INPUT_NAMES.iter().find(|(input, _)| *input == self.input) {
+            return write!(f, "{}", name);
+        }
+        match self.input {
+            Input::Key(KeyCode::F(n)) => write!(f, "f{}", n),
+            Input::Key(KeyCode::Char(c)) => write!(f, "{}", c),
+            input => write!(f, "{:?}", input),
+        }
+    }
+}
+
+type Bindings = Vec<(Command, Vec<Chord>)>;
+
+fn defaults(layout: Layout) -> Bindings {
+    // Chosen by key position so movement stays under the left hand
+    let (up, left, down, right) = match layout {
+        Layout::Qwerty => ("w", "a", "s", "d"),
+        Layout::Azerty => ("z", "q", "s", "d"),
+        Layout::Dvorak => (",", "a", "o", "e"),
+    };
+    let (aim_up, aim_left, aim_down, aim_right) = match layout {
+        Layout::Qwerty | Layout::Azerty => ("i", "j", "k", "l"),
+        Layout::Dvorak => ("c", "h", "t", "n"),
+    };
+    let (previous, next) = match layout {
+        Layout::Qwerty => (["q", "u"], ["e", "o"]),
+        Layout::Azerty => (["a", "u"], ["e", "o"]),
+        Layout::Dvorak => (["'", "g"], [".", "r"]),
+    };
+    let (health, energy) = match layout {
+        Layout::Qwerty => ("z", "x"),
+        Layout::Azerty => ("w", "x"),
+        Layout::Dvorak => (";", "q"),
+    };
+    let (bind_secondary, dash) = match layout {
+        Layout::Qwerty | Layout::Azerty => ("r", "f"),
+        Layout::Dvorak => ("p", "u"),
+    };
+    // AZERTY digits need shift, so the unshifted symbols select spells too
+    let azerty_row = ["&", "é", "\"", "'", "(", "-", "è", "_", "ç"];
+
+    COMMANDS
+        .into_iter()
+        .map(|command| {
+            let keys = match command {
+                Command::Move(Direction::Up) => vec![up, "up"],
+                Command::Move(Direction::Left) => vec![left, "left"],
+                Command::Move(Direction::Down) => vec![down, "down"],
+                Command::Move(Direction::Right) => vec![right, "right"],
+                Command::Evoke(Direction::Up) => vec![aim_up],
+                Command::Evoke(Direction::Left) => vec![aim_left],
+                Command::Evoke(Direction::Down) => vec![aim_down],
+                Command::Evoke(Direction::Right) => vec![aim_right],
+                Command::EvokeMouse => vec!["mouse_left"],
+                Command::EvokeSecondary => vec!["mouse_right"],
+                Command::Swing => vec!["space"],
+                Command::Dash => vec![dash, "mouse_middle"],
+                Command::SelectSpell(ix) if layout == Layout::Azerty => {
+                    vec![DIGITS[ix], azerty_row[ix]]
+                }
+                Command::SelectSpell(ix) => vec![DIGITS[ix]],
+                Command::CycleSpell(false) => [&previous[..], &["scroll_up"]].concat(),
+                Command::CycleSpell(true) => [&next[..], &["scroll_down"]].concat(),
+                Command::BindSecondary => vec![bind_secondary],
+                Command::UseItem(Item::HealthPotion) => vec![health],
+                Command::UseItem(Item::EnergyPotion) => vec![energy],
+                Command::UseItem(_) => vec![],
+                Command::Pause => vec!["home"],
+                Command::Quit => vec!["esc"],
+                Command::DebugInfo => vec!["insert"],
+                Command::Rebind => vec!["f2"],
+                Command::DevConsole => vec!["`", "f12"],
+                // Replay playback
+                Command::FastForward => vec!["tab"],
+                Command::Step => vec!["enter"],
+            };
+            let chords = keys
+                .into_iter()
+                .map(|key| key.parse().expect("default bindings parse"))
+                .collect();
+            (command, chords)
+        })
+        .collect()
+}
+
+const DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+pub struct Keymap {
+    layout: Layout,
+    bindings: Bindings,
+    lookup: HashMap<Chord, Command>,
+}
+
+impl Keymap {
+    pub fn new(layout: Layout) -> Self {
+        Self::from_bindings(layout, defaults(layout)).expect("default bindings are valid")
+    }
+
+    fn from_bindings(layout: Layout, bindings: Bindings) -> Result<Self, String> {
+        let mut lookup = HashMap::new();
+        for (command, chords) in &bindings {
+            for chord in chords {
+                if let Some(other) = lookup.insert(*chord, *command) {
+                    return Err(format!(
+                        "{} is bound to both {} and {}",
+                        chord,
+                        other.name(),
+                        command.name()
+                    ));
+                }
+            }
+        }
+        if !lookup.values().any(|command| *command == Command::Quit) {
+            return Err(String::from("quit must be bound"));
+        }
+
+        Ok(Self {
+            layout,
+            bindings,
+            lookup,
+        })
+    }
+
+    // Commands missing from the file keep the defaults of its layout
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let table: toml::Table = content.parse().map_err(|err| format!("{}", err))?;
+
+        let layout = match table.get("layout") {
+            Some(layout) => layout.as_str().ok_or("layout must be a string")?.parse()?,
+            None => Layout::Qwerty,
+        };
+
+        let mut bindings = defaults(layout);
+        if let Some(overrides) = table.get("bindings") {
+            let overrides = overrides.as_table().ok_or("bindings must be a table")?;
+            for (name, keys) in overrides {
+                let command =
+                    Command::from_name(name).ok_or(format!("Unknown command: {}", name))?;
+                let keys = match keys {
+                    toml::Value::String(key) => vec![key.as_str()],
+                    toml::Value::Array(keys) => keys
+                        .iter()
+                        .map(|key| {
+                            key.as_str()
+                                .ok_or(format!("Keys of {} must be strings", name))
+                        })
+                        .collect::<Result<_, _>>()?,
+                    _ => return Err(format!("Keys of {} must be a list", name)),
+                };
+                let chords = keys
+                    .into_iter()
+                    .map(str::parse)
+                    .collect::<Result<Vec<Chord>, _>>()?;
+
+                if let Some((_, bound)) = bindings.iter_mut().find(|(c, _)| *c == command) {
+                    *bound = chords;
+                }
+            }
+        }
+
+        Self::from_bindings(layout, bindings)
+    }
+
+    pub fn load() -> Self {
+        let Ok(content) = fs::read_to_string(KEYMAP_FILE) else {
+            return Self::new(Layout::Qwerty);
+        };
+        Self::parse(&content).unwrap_or_else(|err| {
+            warn!("Ignoring {}: {}", KEYMAP_FILE, err);
+            Self::new(Layout::Qwerty)
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let mut content = format!("layout = \"{:?}\"\n\n[bindings]\n", self.layout).to_lowercase();
+        for (command, chords) in &self.bindings {
+            let keys = toml::Value::Array(
+                chords
+                    .iter()
+                    .map(|chord| toml::Value::String(chord.to_string()))
+                    .collect(),
+            );
+            content += &format!("{} = {}\n", command.name(), keys);
+        }
+        fs::write(KEYMAP_FILE, content)
+    }
+
+    pub fn chords(&self, command: Command) -> &[Chord] {
+        self.bindings
+            .iter()
+            .find(|(c, _)| *c == command)
+            .map_or(&[], |(_, chords)| chords)
+    }
+
+    // Gameplay command for an input this tick, held commands fire while the
+    // input is active and the rest once when it is pressed
+    pub fn command(&self, state: &InputState, modifiers: KeyModifiers) -> Option<Command> {
+        let (input, held) = match state {
+            InputState::Press(input) => (input, false),
+            InputState::Active(input) => (input, true),
+            InputState::Release(_) => return None,
+        };
+        let command = *self.lookup.get(&Chord::new(*input, modifiers))?;
+        (command.is_held() == held && !command.is_system()).then_some(command)
+    }
+
+    pub fn system_command(&self, event: &Event) -> Option<Command> {
+        self.pressed(event).filter(Command::is_system)
+    }
+
+    // Any command bound to the chord an event starts, for menus
+    pub fn pressed(&self, event: &Event) -> Option<Command> {
+        self.lookup.get(&Chord::pressed(event)?).copied()
+    }
+
+    // The first chord bound to `command`, as shown in menus
+    pub fn label(&self, command: Command) -> String {
+        self.chords(command)
+            .first()
+            .map_or_else(|| String::from("-"), Chord::to_string)
+    }
+
+    // Moves the chord to `command`, returning the command it was taken from
+    pub fn bind(&mut self, command: Command, chord: Chord) -> Result<Option<Command>, String> {
+        let previous = self.lookup.get(&chord).copied().filter(|c| *c != command);
+
+        let mut bindings = self.bindings.clone();
+        for (c, chords) in bindings.iter_mut() {
+            if *c == command {
+                *chords = vec![chord];
+            } else {
+                chords.retain(|bound| *bound != chord);
+            }
+        }
+
+        *self = Self::from_bindings(self.layout, bindings)?;
+        Ok(previous)
+    }
+
+    fn reset(&mut self, layout: Layout) {
+        *self = Self::new(layout);
+    }
+
+    fn describe(&self, command: Command) -> String {
+        let chords: Vec<String> = self.chords(command).iter().map(Chord::to_string).collect();
+        format!("{:<15} {}", command.name(), chords.join(", "))
+    }
+
+    pub fn rebind_screen(&mut self, display: &mut impl Display) -> io::Result<()> {
+        let groups: Vec<(&str, Vec<Command>)> = GROUPS
+            .into_iter()
+            .map(|group| {
+                let commands = COMMANDS
+                    .into_iter()
+                    .filter(|command| command.group() == group)
+                    .collect();
+                (group, commands)
+            })
+            .collect();
+        let layouts = [Layout::Qwerty, Layout::Azerty, Layout::Dvorak];
+
+        let mut title = String::from("Key bindings");
+        let mut changed = false;
+        loop {
+            let options: Vec<String> = groups
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .chain(
+                    layouts
+                        .iter()
+                        .map(|layout| format!("Reset to {:?} defaults", layout)),
+                )
+                .collect();
+
+            let Some(ix) = menu::choose(display, self, &title, &options)? else {
+                break;
+            };
+            if ix >= groups.len() {
+                self.reset(layouts[ix - groups.len()]);
+                title = format!("Key bindings reset to {:?}", self.layout);
+                changed = true;
+                continue;
+            }
+
+            let (name, commands) = &groups[ix];
+            let options: Vec<String> = commands.iter().map(|c| self.describe(*c)).collect();
+            let Some(ix) = menu::choose(display, self, name, &options)? else {
+                continue;
+            };
+            let command = commands[ix];
+
+            display.draw_summary(
+                &format!("Press the new input for {}", command.name()),
+                &[String::from("Esc cancels")],
+            )?;
+            let chord = loop {
+                if let Some(chord) = Chord::pressed(&read()?) {
+                    break chord;
+                }
+            };
+            if chord.input == Input::Key(KeyCode::Esc) && chord.modifiers.is_empty() {
+                continue;
+            }
+
+            title = match self.bind(command, chord) {
+                Ok(Some(previous)) => {
+                    format!(
+                        "{} moved from {} to {}",
+                        chord,
+                        previous.name(),
+                        command.name()
+                    )
+                }
+                Ok(None) => format!("{} bound to {}", chord, command.name()),
+                Err(err) => err,
+            };
+            changed = true;
+        }
+
+        if changed {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_round_trip() {
+        for name in ["ctrl+s", "alt+shift+up", "mouse_right", "f2", "plus", "é"] {
+            assert_eq!(name.parse::<Chord>().unwrap().to_string(), name);
+        }
+        assert_eq!(
+            "shift+W".parse::<Chord>().unwrap(),
+            Chord::new(Input::Key(KeyCode::Char('w')), KeyModifiers::empty())
+        );
+        assert!("hyper+w".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn layout_defaults_have_no_conflicts() {
+        for layout in ["qwerty", "azerty", "dvorak"] {
+            assert!(Keymap::parse(&format!("layout = \"{}\"", layout)).is_ok());
+        }
+    }
+
+    #[test]
+    fn overrides_are_checked_for_conflicts() {
+        let keymap = Keymap::parse("[bindings]\nswing = [\"ctrl+x\", \"scroll_left\"]").unwrap();
+        assert_eq!(
+            keymap.system_command(&Event::Key(KeyEvent::new(
+                KeyCode::Esc,
+                KeyModifiers::empty()
+            ))),
+            Some(Command::Quit)
+        );
+        assert_eq!(
+            keymap.command(
+                &InputState::Press(Input::Key(KeyCode::Char('x'))),
+                KeyModifiers::CONTROL
+            ),
+            Some(Command::Swing)
+        );
+
+        let err = Keymap::parse("[bindings]\nswing = [\"w\"]").err().unwrap();
+        assert_eq!(err, "w is bound to both move_up and swing");
+        assert!(Keymap::parse("[bindings]\nquit = []").is_err());
+    }
+
+    #[test]
+    fn menus_follow_rebound_keys() {
+        let keymap =
+            Keymap::parse("[bindings]\nspell_1 = [\"f5\"]\nquit = [\"backspace\"]").unwrap();
+        let press = |code| Event::Key(KeyEvent::new(code, KeyModifiers::empty()));
+
+        assert_eq!(
+            keymap.pressed(&press(KeyCode::F(5))),
+            Some(Command::SelectSpell(0))
+        );
+        assert_eq!(keymap.pressed(&press(KeyCode::Char('1'))), None);
+        assert_eq!(
+            keymap.pressed(&press(KeyCode::Backspace)),
+            Some(Command::Quit)
+        );
+        assert_eq!(keymap.label(Command::SelectSpell(0)), "f5");
+    }
+}
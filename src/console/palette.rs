@@ -0,0 +1,326 @@
+use std::{env, str::FromStr, sync::OnceLock};
+
+use crossterm::style::Color;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Theme {
+    Default,
+    HighContrast,
+    // Okabe-Ito colors, red and green stay apart for deuteranopia and protanopia
+    Colorblind,
+    Monochrome,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Palette {
+    pub theme: Theme,
+    pub depth: ColorDepth,
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Theme::Default),
+            "high_contrast" => Ok(Theme::HighContrast),
+            "colorblind" => Ok(Theme::Colorblind),
+            "monochrome" => Ok(Theme::Monochrome),
+            _ => Err(format!(
+                "Unknown palette: {}, expected default, high_contrast, colorblind or monochrome",
+                s
+            )),
+        }
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Ansi256),
+            "16" => Ok(ColorDepth::Ansi16),
+            _ => Err(format!(
+                "Unknown colors: {}, expected truecolor, 256 or 16",
+                s
+            )),
+        }
+    }
+}
+
+impl Theme {
+    // NO_COLOR asks for no color at all, see no-color.org
+    pub fn detect() -> Self {
+        match env::var("NO_COLOR") {
+            Ok(value) if !value.is_empty() => Theme::Monochrome,
+            _ => Theme::Default,
+        }
+    }
+
+    fn apply(self, color: Color) -> Color {
+        if color == Color::Reset {
+            return color;
+        }
+
+        match self {
+            Theme::Default => color,
+            Theme::HighContrast => match color {
+                Color::DarkRed => Color::Red,
+                Color::DarkGreen => Color::Green,
+                Color::DarkYellow => Color::Yellow,
+                Color::DarkBlue => Color::Blue,
+                Color::DarkMagenta => Color::Magenta,
+                Color::DarkCyan => Color::Cyan,
+                Color::Grey => Color::White,
+                Color::DarkGrey => Color::Grey,
+                // The tiled floor goes black so units stand out
+                Color::Rgb { .. } if luminance(color) < 128 => Color::Black,
+                Color::Rgb { .. } => Color::White,
+                _ => color,
+            },
+            Theme::Colorblind => match color {
+                Color::Red => Color::Rgb {
+                    r: 213,
+                    g: 94,
+                    b: 0,
+                },
+                Color::DarkRed => Color::Rgb {
+                    r: 230,
+                    g: 159,
+                    b: 0,
+                },
+                Color::Green => Color::Rgb {
+                    r: 0,
+                    g: 158,
+                    b: 115,
+                },
+                Color::DarkGreen => Color::Rgb {
+                    r: 0,
+                    g: 114,
+                    b: 178,
+                },
+                Color::Yellow => Color::Rgb {
+                    r: 240,
+                    g: 228,
+                    b: 66,
+                },
+                Color::Magenta => Color::Rgb {
+                    r: 204,
+                    g: 121,
+                    b: 167,
+                },
+                Color::Cyan => Color::Rgb {
+                    r: 86,
+                    g: 180,
+                    b: 233,
+                },
+                _ => color,
+            },
+            Theme::Monochrome => {
+                let grey = luminance(color);
+                Color::Rgb {
+                    r: grey,
+                    g: grey,
+                    b: grey,
+                }
+            }
+        }
+    }
+}
+
+impl ColorDepth {
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        Self::from_env(&colorterm, &term)
+    }
+
+    // Only the consoles known to be stuck on 16 colors get them, anything
+    // else can be turned down with --colors
+    fn from_env(colorterm: &str, term: &str) -> Self {
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term == "linux" || term == "dumb" || term.starts_with("vt") {
+            ColorDepth::Ansi16
+        } else {
+            ColorDepth::Ansi256
+        }
+    }
+
+    fn apply(self, color: Color) -> Color {
+        match (self, color, rgb(color)) {
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }, _) => Color::AnsiValue(ansi256(r, g, b)),
+            (ColorDepth::Ansi16, Color::Rgb { .. } | Color::AnsiValue(_), Some(target)) => ANSI16
+                .into_iter()
+                .min_by_key(|named| distance(target, rgb(*named).unwrap()))
+                .unwrap(),
+            _ => color,
+        }
+    }
+}
+
+impl Palette {
+    pub fn install(self) {
+        let _ = PALETTE.set(self);
+    }
+
+    pub fn current() -> Self {
+        PALETTE.get().copied().unwrap_or(Palette {
+            theme: Theme::Default,
+            depth: ColorDepth::TrueColor,
+        })
+    }
+
+    pub fn apply(self, color: Color) -> Color {
+        self.depth.apply(self.theme.apply(color))
+    }
+}
+
+const ANSI16: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+// xterm's defaults, terminals vary but this is close enough for matching
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Black => Some((0, 0, 0)),
+        Color::DarkRed => Some((128, 0, 0)),
+        Color::DarkGreen => Some((0, 128, 0)),
+        Color::DarkYellow => Some((128, 128, 0)),
+        Color::DarkBlue => Some((0, 0, 128)),
+        Color::DarkMagenta => Some((128, 0, 128)),
+        Color::DarkCyan => Some((0, 128, 128)),
+        Color::Grey => Some((192, 192, 192)),
+        Color::DarkGrey => Some((128, 128, 128)),
+        Color::Red => Some((255, 0, 0)),
+        Color::Green => Some((0, 255, 0)),
+        Color::Yellow => Some((255, 255, 0)),
+        Color::Blue => Some((0, 0, 255)),
+        Color::Magenta => Some((255, 0, 255)),
+        Color::Cyan => Some((0, 255, 255)),
+        Color::White => Some((255, 255, 255)),
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(value) if value < 16 => rgb(ANSI16[value as usize]),
+        Color::AnsiValue(value) if value < 232 => {
+            let level = |i: u8| if i == 0 { 0 } else { 55 + 40 * i };
+            let cube = value - 16;
+            Some((level(cube / 36), level(cube / 6 % 6), level(cube % 6)))
+        }
+        Color::AnsiValue(value) => {
+            let grey = 8 + 10 * (value - 232);
+            Some((grey, grey, grey))
+        }
+        Color::Reset => None,
+    }
+}
+
+fn luminance(color: Color) -> u8 {
+    let (r, g, b) = rgb(color).unwrap_or((255, 255, 255));
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+// Nearest of the 6x6x6 cube and the grey ramp
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let step = |c: u8| {
+        if c < 48 {
+            0
+        } else {
+            ((c as u16 - 35) / 40) as u8
+        }
+    };
+    let cube = 16 + 36 * step(r) + 6 * step(g) + step(b);
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    let target = (r, g, b);
+    [cube, grey]
+        .into_iter()
+        .min_by_key(|value| distance(target, rgb(Color::AnsiValue(*value)).unwrap()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_downgrade_to_the_nearest_available() {
+        let floor = Color::Rgb { r: 4, g: 107, b: 2 };
+        assert_eq!(ColorDepth::Ansi16.apply(floor), Color::DarkGreen);
+        assert_eq!(ColorDepth::Ansi256.apply(floor), Color::AnsiValue(22));
+        assert_eq!(ColorDepth::TrueColor.apply(floor), floor);
+        assert_eq!(ColorDepth::Ansi16.apply(Color::Red), Color::Red);
+        assert_eq!(ColorDepth::Ansi256.apply(Color::Reset), Color::Reset);
+        assert_eq!(
+            ColorDepth::Ansi256.apply(Color::Rgb {
+                r: 128,
+                g: 128,
+                b: 128
+            }),
+            Color::AnsiValue(244)
+        );
+    }
+
+    #[test]
+    fn unknown_terminals_get_256_colors() {
+        assert_eq!(
+            ColorDepth::from_env("truecolor", "xterm"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env("", "xterm-256color"),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::from_env("", "xterm"), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env("", "screen"), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env("", "linux"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "vt100"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "dumb"), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn monochrome_leaves_only_greys() {
+        let palette = Palette {
+            theme: Theme::Monochrome,
+            depth: ColorDepth::Ansi16,
+        };
+        for color in ANSI16 {
+            assert!(matches!(
+                palette.apply(color),
+                Color::Black | Color::DarkGrey | Color::Grey | Color::White
+            ));
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor, queue,
+    style::{self, Color, Colors},
+    terminal,
+};
+use nalgebra::{Point2, Vector2};
+use unicode_width::UnicodeWidthChar;
+
+use super::Palette;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Cell {
+    Glyph { symbol: char, fg: Color, bg: Color },
+    // Right half of a double width glyph
+    Continuation,
+}
+
+const BLANK: Cell = Cell::Glyph {
+    symbol: ' ',
+    fg: Color::Reset,
+    bg: Color::Reset,
+};
+
+fn width(symbol: char) -> u16 {
+    symbol.width().unwrap_or(1).clamp(1, 2) as u16
+}
+
+// Cells drawn for the next frame, diffed against what the terminal already
+// shows so only changed cells are written out
+pub struct Screen {
+    size: Vector2<u16>,
+    back: Vec<Cell>,
+    // Effects, shown over the back cells without replacing them
+    overlay: Vec<Option<Cell>>,
+    // None where the terminal contents are unknown
+    front: Vec<Option<Cell>>,
+}
+
+impl Screen {
+    pub fn new(size: Vector2<u16>) -> Self {
+        let len = size.x as usize * size.y as usize;
+        Self {
+            size,
+            back: vec![BLANK; len],
+            overlay: vec![None; len],
+            front: vec![None; len],
+        }
+    }
+
+    fn index(&self, spot: Point2<u16>) -> Option<usize> {
+        (spot.x < self.size.x && spot.y < self.size.y)
+            .then(|| spot.y as usize * self.size.x as usize + spot.x as usize)
+    }
+
+    pub fn clear(&mut self) {
+        self.back.fill(BLANK);
+    }
+
+    pub fn clear_overlay(&mut self) {
+        self.overlay.fill(None);
+    }
+
+    // Next present repaints every cell
+    pub fn invalidate(&mut self) {
+        self.front.fill(None);
+    }
+
+    // Returns the column following the glyph, off screen glyphs are dropped
+    pub fn put(&mut self, spot: Point2<u16>, symbol: char, fg: Color, bg: Color) -> u16 {
+        let next = spot.x.saturating_add(width(symbol));
+        let Some(ix) = self.index(spot) else {
+            return next;
+        };
+        let right = self.index(Point2::new(spot.x + 1, spot.y));
+
+        self.split(ix);
+        match (width(symbol), right) {
+            (1, _) => self.back[ix] = Cell::Glyph { symbol, fg, bg },
+            (_, Some(right)) => {
+                self.split(right);
+                self.back[ix] = Cell::Glyph { symbol, fg, bg };
+                self.back[right] = Cell::Continuation;
+            }
+            // No room for the right half on the last column
+            (_, None) => {
+                self.back[ix] = Cell::Glyph {
+                    symbol: ' ',
+                    fg,
+                    bg,
+                }
+            }
+        }
+        next
+    }
+
+    pub fn put_str(&mut self, spot: Point2<u16>, text: &str, fg: Color, bg: Color) -> u16 {
+        let mut x = spot.x;
+        for symbol in text.chars() {
+            x = self.put(Point2::new(x, spot.y), symbol, fg, bg);
+        }
+        x
+    }
+
+    pub fn overlay(&mut self, spot: Point2<u16>, symbol: char, fg: Color, bg: Color) -> u16 {
+        let next = spot.x.saturating_add(width(symbol));
+        let Some(ix) = self.index(spot) else {
+            return next;
+        };
+
+        match (width(symbol), self.index(Point2::new(spot.x + 1, spot.y))) {
+            (1, _) => self.overlay[ix] = Some(Cell::Glyph { symbol, fg, bg }),
+            (_, Some(right)) => {
+                self.overlay[ix] = Some(Cell::Glyph { symbol, fg, bg });
+                self.overlay[right] = Some(Cell::Continuation);
+            }
+            (_, None) => {
+                self.overlay[ix] = Some(Cell::Glyph {
+                    symbol: ' ',
+                    fg,
+                    bg,
+                })
+            }
+        }
+        next
+    }
+
+    // Recolors whatever is shown at the spot
+    pub fn tint(&mut self, spot: Point2<u16>, fg: Option<Color>, bg: Option<Color>) {
+        let Some(ix) = self.index(spot) else {
+            return;
+        };
+        if let Cell::Glyph {
+            symbol,
+            fg: shown_fg,
+            bg: shown_bg,
+        } = self.shown(ix)
+        {
+            self.overlay[ix] = Some(Cell::Glyph {
+                symbol,
+                fg: fg.unwrap_or(shown_fg),
+                bg: bg.unwrap_or(shown_bg),
+            });
+        }
+    }
+
+    pub fn is_blank(&self, spot: Point2<u16>) -> bool {
+        self.index(spot)
+            .is_some_and(|ix| matches!(self.back[ix], Cell::Glyph { symbol: ' ', .. }))
+    }
+
+    fn shown(&self, ix: usize) -> Cell {
+        self.overlay[ix].unwrap_or(self.back[ix])
+    }
+
+    fn is_wide(&self, ix: usize) -> bool {
+        matches!(self.shown(ix), Cell::Glyph { symbol, .. } if width(symbol) == 2)
+    }
+
+    // Blanks what remains of a double width glyph once either half is overwritten
+    fn split(&mut self, ix: usize) {
+        let column = (ix % self.size.x as usize) as u16;
+        match self.back[ix] {
+            Cell::Continuation if column > 0 => {
+                if let Cell::Glyph { fg, bg, .. } = self.back[ix - 1] {
+                    self.back[ix - 1] = Cell::Glyph {
+                        symbol: ' ',
+                        fg,
+                        bg,
+                    };
+                }
+            }
+            Cell::Glyph { symbol, fg, bg } if width(symbol) == 2 && column + 1 < self.size.x => {
+                self.back[ix + 1] = Cell::Glyph {
+                    symbol: ' ',
+                    fg,
+                    bg,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    // Queues the changed cells and flushes once
+    pub fn present(&mut self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, terminal::BeginSynchronizedUpdate)?;
+
+        let palette = Palette::current();
+        let mut cursor: Option<Point2<u16>> = None;
+        let mut colors: Option<(Color, Color)> = None;
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let ix = y as usize * self.size.x as usize + x as usize;
+                let mut cell = self.shown(ix);
+                // An overlay glyph can cover the left half of a wide board glyph
+                if cell == Cell::Continuation && !(x > 0 && self.is_wide(ix - 1)) {
+                    cell = BLANK;
+                }
+                if self.front[ix] == Some(cell) {
+                    continue;
+                }
+                self.front[ix] = Some(cell);
+
+                let Cell::Glyph { symbol, fg, bg } = cell else {
+                    continue;
+                };
+                if cursor != Some(Point2::new(x, y)) {
+                    queue!(out, cursor::MoveTo(x, y))?;
+                }
+                if colors != Some((fg, bg)) {
+                    queue!(
+                        out,
+                        style::SetColors(Colors::new(palette.apply(fg), palette.apply(bg)))
+                    )?;
+                    colors = Some((fg, bg));
+                }
+                queue!(out, style::Print(symbol))?;
+                // Terminals disagree on some emoji widths, move explicitly after them
+                cursor = (width(symbol) == 1).then_some(Point2::new(x + 1, y));
+            }
+        }
+
+        queue!(out, style::ResetColor, terminal::EndSynchronizedUpdate)?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_changed_cells_are_written() {
+        let mut screen = Screen::new(Vector2::new(10, 2));
+        screen.put_str(Point2::new(0, 0), "abc", Color::White, Color::Black);
+        let mut out = Vec::new();
+        screen.present(&mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains("abc"));
+
+        let mut unchanged = Vec::new();
+        screen.put_str(Point2::new(0, 0), "abc", Color::White, Color::Black);
+        screen.present(&mut unchanged).unwrap();
+        let mut changed = Vec::new();
+        screen.put(Point2::new(1, 0), 'x', Color::White, Color::Black);
+        screen.present(&mut changed).unwrap();
+        assert!(changed.len() > unchanged.len());
+        assert!(!String::from_utf8_lossy(&changed).contains('a'));
+        assert!(String::from_utf8_lossy(&changed).contains('x'));
+    }
+
+    #[test]
+    fn overwriting_half_a_wide_glyph_blanks_the_other_half() {
+        let mut screen = Screen::new(Vector2::new(4, 1));
+        assert_eq!(
+            screen.put(Point2::new(0, 0), '🚪', Color::White, Color::Black),
+            2
+        );
+        assert_eq!(screen.back[1], Cell::Continuation);
+
+        screen.put(Point2::new(1, 0), 'x', Color::White, Color::Black);
+        assert!(matches!(screen.back[0], Cell::Glyph { symbol: ' ', .. }));
+
+        screen.put(Point2::new(3, 0), '🚪', Color::White, Color::Black);
+        assert!(matches!(screen.back[3], Cell::Glyph { symbol: ' ', .. }));
+    }
+
+    #[test]
+    fn cleared_overlay_shows_the_board_again() {
+        let mut screen = Screen::new(Vector2::new(4, 1));
+        screen.put(Point2::new(0, 0), 'm', Color::White, Color::Black);
+        screen.present(&mut Vec::new()).unwrap();
+
+        screen.overlay(Point2::new(0, 0), '*', Color::Red, Color::Black);
+        let mut out = Vec::new();
+        screen.present(&mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains('*'));
+
+        screen.clear_overlay();
+        let mut out = Vec::new();
+        screen.present(&mut out).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains('m'));
+        assert_eq!(screen.back[0], screen.shown(0));
+    }
+}
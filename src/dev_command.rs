@@ -0,0 +1,276 @@
+use std::{fmt, str::FromStr};
+
+use nalgebra::Point2;
+
+use crate::{
+    equipment::GEAR,
+    item::{Item, CONSUMABLES},
+    magic::Spell,
+    monster::{Archetype, ARCHETYPES},
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Gift {
+    Spell(Spell),
+    Item(Item),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DevCommand {
+    Spawn(Archetype, Option<Point2<f64>>),
+    God,
+    Energy(u32),
+    Give(Gift),
+    Teleport(Point2<f64>),
+    Speed(f64),
+    KillAll,
+}
+
+const NAMES: [&str; 7] = ["spawn", "god", "energy", "give", "tp", "speed", "kill"];
+
+const CASTABLE: [Spell; 8] = [
+    Spell::Fireball,
+    Spell::Sphere,
+    Spell::Inferno,
+    Spell::Spark,
+    Spell::Orb,
+    Spell::Arrow,
+    Spell::Sweep,
+    Spell::Summon,
+];
+
+// OakStaff -> oak_staff
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn archetype_name(archetype: &Archetype) -> String {
+    snake_case(&format!("{:?}", archetype))
+}
+
+fn gift_name(gift: &Gift) -> String {
+    match gift {
+        Gift::Spell(spell) => snake_case(&format!("{:?}", spell)),
+        Gift::Item(Item::Gear(gear)) => snake_case(&format!("{:?}", gear)),
+        Gift::Item(item) => snake_case(&format!("{:?}", item)),
+    }
+}
+
+fn gifts() -> Vec<Gift> {
+    CASTABLE
+        .into_iter()
+        .map(Gift::Spell)
+        .chain(CONSUMABLES.into_iter().map(Gift::Item))
+        .chain(GEAR.into_iter().map(|gear| Gift::Item(Item::Gear(gear))))
+        .collect()
+}
+
+// Words that can follow `words`, used for tab completion
+fn candidates(words: &[&str]) -> Vec<String> {
+    match words {
+        [] => NAMES.iter().map(|name| name.to_string()).collect(),
+        ["spawn"] => ARCHETYPES.iter().map(archetype_name).collect(),
+        ["give"] => gifts().iter().map(gift_name).collect(),
+        ["kill"] => vec![String::from("all")],
+        _ => vec![],
+    }
+}
+
+// Completes the last word as far as it is unambiguous, along with the
+// remaining matches when there is more than one
+pub fn complete(line: &str) -> (String, Vec<String>) {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    let partial = if line.ends_with(' ') || line.is_empty() {
+        ""
+    } else {
+        words.pop().unwrap_or_default()
+    };
+
+    let matches: Vec<String> = candidates(&words)
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(partial))
+        .collect();
+
+    let Some(first) = matches.first() else {
+        return (line.to_string(), matches);
+    };
+    let common = matches.iter().fold(first.clone(), |common, candidate| {
+        common
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a)
+            .collect()
+    });
+
+    let mut completed = words.join(" ");
+    if !completed.is_empty() {
+        completed.push(' ');
+    }
+    completed += &common;
+    if matches.len() == 1 {
+        completed.push(' ');
+    }
+
+    (completed, if matches.len() > 1 { matches } else { vec![] })
+}
+
+fn parse_number<T: FromStr>(word: Option<&str>, what: &str) -> Result<T, String> {
+    let word = word.ok_or(format!("Missing {}", what))?;
+    word.parse()
+        .map_err(|_| format!("Invalid {}: {}", what, word))
+}
+
+// Rust parses "nan" and "inf" too, neither is a place on the map
+fn parse_coordinate(word: Option<&str>, what: &str) -> Result<f64, String> {
+    let value: f64 = parse_number(word, what)?;
+    if !value.is_finite() {
+        return Err(format!("Invalid {}: {}", what, word.unwrap_or_default()));
+    }
+    Ok(value)
+}
+
+impl FromStr for DevCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let name = words.next().ok_or("Type a command, tab completes")?;
+
+        let command = match name {
+            "spawn" => {
+                let monster = words.next().ok_or("Missing monster")?;
+                let archetype = *ARCHETYPES
+                    .iter()
+                    .find(|archetype| archetype_name(archetype) == monster)
+                    .ok_or(format!("Unknown monster: {}", monster))?;
+                let location = match words.next() {
+                    Some(x) => Some(Point2::new(
+                        parse_coordinate(Some(x), "x")?,
+                        parse_coordinate(words.next(), "y")?,
+                    )),
+                    None => None,
+                };
+                DevCommand::Spawn(archetype, location)
+            }
+            "god" => DevCommand::God,
+            "energy" => DevCommand::Energy(parse_number(words.next(), "energy")?),
+            "give" => {
+                let what = words.next().ok_or("Missing spell or item")?;
+                let gift = gifts()
+                    .into_iter()
+                    .find(|gift| gift_name(gift) == what)
+                    .ok_or(format!("Unknown spell or item: {}", what))?;
+                DevCommand::Give(gift)
+            }
+            "tp" => DevCommand::Teleport(Point2::new(
+                parse_coordinate(words.next(), "x")?,
+                parse_coordinate(words.next(), "y")?,
+            )),
+            "speed" => {
+                let factor: f64 = parse_number(words.next(), "speed")?;
+                if !(0.05..=10.).contains(&factor) {
+                    return Err(String::from("Speed must be between 0.05 and 10"));
+                }
+                DevCommand::Speed(factor)
+            }
+            "kill" => match words.next() {
+                Some("all") => DevCommand::KillAll,
+                _ => return Err(String::from("Usage: kill all")),
+            },
+            _ => return Err(format!("Unknown command: {}", name)),
+        };
+
+        match words.next() {
+            Some(extra) => Err(format!("Unexpected: {}", extra)),
+            None => Ok(command),
+        }
+    }
+}
+
+impl fmt::Display for DevCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DevCommand::Spawn(archetype, None) => write!(f, "spawn {}", archetype_name(archetype)),
+            DevCommand::Spawn(archetype, Some(location)) => write!(
+                f,
+                "spawn {} {} {}",
+                archetype_name(archetype),
+                location.x,
+                location.y
+            ),
+            DevCommand::God => write!(f, "god"),
+            DevCommand::Energy(energy) => write!(f, "energy {}", energy),
+            DevCommand::Give(gift) => write!(f, "give {}", gift_name(gift)),
+            DevCommand::Teleport(location) => write!(f, "tp {} {}", location.x, location.y),
+            DevCommand::Speed(factor) => write!(f, "speed {}", factor),
+            DevCommand::KillAll => write!(f, "kill all"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_parse_and_print_back() {
+        for line in [
+            "spawn stalker 10 5",
+            "spawn drifter",
+            "god",
+            "energy 100",
+            "give inferno",
+            "give oak_staff",
+            "tp 3 3",
+            "speed 0.5",
+            "kill all",
+        ] {
+            assert_eq!(line.parse::<DevCommand>().unwrap().to_string(), line);
+        }
+
+        assert_eq!(
+            "spawn dragon 10 5".parse::<DevCommand>(),
+            Ok(DevCommand::Spawn(
+                Archetype::Dragon,
+                Some(Point2::new(10., 5.))
+            ))
+        );
+        assert_eq!(
+            "spawn hydra 10 5".parse::<DevCommand>(),
+            Err(String::from("Unknown monster: hydra"))
+        );
+        assert!("tp 3".parse::<DevCommand>().is_err());
+        assert_eq!(
+            "tp nan 0".parse::<DevCommand>(),
+            Err(String::from("Invalid x: nan"))
+        );
+        assert!("spawn stalker inf 1".parse::<DevCommand>().is_err());
+        assert!("spawn stalker 1 -infinity".parse::<DevCommand>().is_err());
+        assert!("god mode".parse::<DevCommand>().is_err());
+    }
+
+    #[test]
+    fn tab_completes_common_prefix() {
+        assert_eq!(complete("ki"), (String::from("kill "), vec![]));
+        assert_eq!(complete("kill "), (String::from("kill all "), vec![]));
+        assert_eq!(
+            complete("sp"),
+            (
+                String::from("sp"),
+                vec![String::from("spawn"), String::from("speed")]
+            )
+        );
+        assert_eq!(complete("give s").0, "give s");
+        assert_eq!(complete("give sw").0, "give sw");
+        assert_eq!(complete("give swe").0, "give sweep ");
+        assert_eq!(complete("tp 3").0, "tp 3");
+    }
+}
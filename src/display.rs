@@ -6,4 +6,9 @@ pub trait Display {
     fn enqueue_action(&mut self, action: RenderAction);
     fn draw_initial(&mut self, state: &State) -> io::Result<()>;
     fn draw(&mut self, state: &State) -> io::Result<()>;
+    fn draw_menu(&mut self, title: &str, options: &[String]) -> io::Result<()>;
+    fn draw_summary(&mut self, title: &str, lines: &[String]) -> io::Result<()>;
+    fn draw_console(&mut self, input: &str, message: &str) -> io::Result<()>;
+    // False when the terminal is too small to hold the frame
+    fn resize(&mut self, cols: u16, rows: u16) -> io::Result<bool>;
 }
@@ -1,9 +1,19 @@
+pub mod ally;
+pub mod collision;
+pub mod equipment;
+pub mod experience;
+pub mod item;
 pub mod monster;
 pub mod object;
+pub mod physics;
 pub mod player;
+pub mod spatial;
+pub mod stats;
 
 use nalgebra::Point2;
 
+use collision::Hitbox;
+
 pub trait Unit {
     fn set_location(&mut self, location: Point2<f64>, ticker: u128);
     fn speed(&self) -> f64;
@@ -11,4 +21,5 @@ pub trait Unit {
 
 pub trait Entity {
     fn location(&self) -> Point2<f64>;
+    fn hitbox(&self) -> Hitbox;
 }
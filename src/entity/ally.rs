@@ -0,0 +1,108 @@
+use nalgebra::Point2;
+
+use crate::collision::Hitbox;
+use crate::magic::Spell;
+
+use super::*;
+
+const WRAITH_DURATION: u128 = 5_000;
+const WRAITH_SPEED: f64 = 3.;
+pub const WRAITH_DAMAGE: u32 = 1;
+pub const WRAITH_KNOCKBACK: f64 = 1.;
+// Monsters further away than this go unnoticed
+pub const WRAITH_SIGHT: f64 = 8.;
+const STRIKE_COOLDOWN: u128 = 600;
+
+// Fights on the player's side until it fades, passing through monsters
+pub struct Ally {
+    pub location: Point2<f64>,
+    // Kills are credited to the ability that raised the ally
+    pub spell: Spell,
+    speed: f64,
+    until: u128,
+    last_tick: u128,
+    last_strike: Option<u128>,
+}
+
+impl Ally {
+    pub fn wraith(location: Point2<f64>, ticker: u128) -> Self {
+        Self {
+            location,
+            spell: Spell::Summon,
+            speed: WRAITH_SPEED / 1000.,
+            until: ticker + WRAITH_DURATION,
+            last_tick: ticker,
+            last_strike: None,
+        }
+    }
+
+    pub fn expired(&self, ticker: u128) -> bool {
+        ticker >= self.until
+    }
+
+    pub fn set_ticker(&mut self, ticker: u128) {
+        self.last_tick = ticker;
+    }
+
+    // Heads straight for the target without overshooting it
+    pub fn seek(&self, target: Point2<f64>, ticker: u128) -> Point2<f64> {
+        let offset = target - self.location;
+        let step = self.speed * ticker.saturating_sub(self.last_tick) as f64;
+        if offset.magnitude() <= step {
+            target
+        } else {
+            self.location + offset.normalize() * step
+        }
+    }
+
+    pub fn can_strike(&self, ticker: u128) -> bool {
+        self.last_strike
+            .is_none_or(|last_strike| ticker >= last_strike + STRIKE_COOLDOWN)
+    }
+
+    pub fn strike(&mut self, ticker: u128) {
+        self.last_strike = Some(ticker);
+    }
+}
+
+impl Unit for Ally {
+    fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    fn set_location(&mut self, location: Point2<f64>, ticker: u128) {
+        self.location = location;
+        self.last_tick = ticker;
+    }
+}
+
+impl Entity for Ally {
+    fn location(&self) -> Point2<f64> {
+        self.location
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::circle(0.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraith_closes_in_then_fades() {
+        let mut wraith = Ally::wraith(Point2::new(0., 0.), 0);
+        let step = wraith.seek(Point2::new(10., 0.), 1_000);
+        assert!((step.x - WRAITH_SPEED).abs() < 1e-9);
+        assert_eq!(wraith.seek(Point2::new(1., 0.), 1_000), Point2::new(1., 0.));
+
+        assert!(wraith.can_strike(100));
+        wraith.strike(100);
+        assert!(!wraith.can_strike(100 + STRIKE_COOLDOWN - 1));
+        assert!(wraith.can_strike(100 + STRIKE_COOLDOWN));
+
+        assert!(!wraith.expired(WRAITH_DURATION - 1));
+        assert!(wraith.expired(WRAITH_DURATION));
+    }
+}
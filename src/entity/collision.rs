@@ -0,0 +1,205 @@
+use nalgebra::{vector, Point2, Vector2};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Hitbox {
+    Circle { radius: f64 },
+    // Axis-aligned, may span several cells
+    Box { half_extents: Vector2<f64> },
+}
+
+// Largest reach of any unit, bounds proximity queries against units, trolls
+// reach the furthest
+pub const MAX_UNIT_REACH: f64 = 2.;
+// Largest reach of any object, steam spreads the widest
+pub const MAX_OBJECT_REACH: f64 = 0.8;
+
+impl Hitbox {
+    pub const fn circle(radius: f64) -> Self {
+        Hitbox::Circle { radius }
+    }
+
+    pub const fn rect(half_width: f64, half_height: f64) -> Self {
+        Hitbox::Box {
+            half_extents: vector![half_width, half_height],
+        }
+    }
+
+    // Radius of the smallest circle containing the hitbox
+    pub fn reach(&self) -> f64 {
+        match self {
+            Hitbox::Circle { radius } => *radius,
+            Hitbox::Box { half_extents } => half_extents.magnitude(),
+        }
+    }
+
+    fn half_extents(&self) -> Vector2<f64> {
+        match self {
+            Hitbox::Circle { radius } => vector![*radius, *radius],
+            Hitbox::Box { half_extents } => *half_extents,
+        }
+    }
+
+    // The area where the center of `other` overlaps this hitbox, circles
+    // against boxes are treated as their bounding boxes
+    pub fn expand(&self, other: &Hitbox) -> Hitbox {
+        match (self, other) {
+            (Hitbox::Circle { radius: a }, Hitbox::Circle { radius: b }) => Hitbox::circle(a + b),
+            _ => Hitbox::Box {
+                half_extents: self.half_extents() + other.half_extents(),
+            },
+        }
+    }
+
+    pub fn contains(&self, offset: Vector2<f64>) -> bool {
+        match self {
+            Hitbox::Circle { radius } => offset.magnitude() < *radius,
+            Hitbox::Box { half_extents } => {
+                offset.x.abs() < half_extents.x && offset.y.abs() < half_extents.y
+            }
+        }
+    }
+}
+
+pub fn overlaps(a: Point2<f64>, a_hitbox: Hitbox, b: Point2<f64>, b_hitbox: Hitbox) -> bool {
+    a_hitbox.expand(&b_hitbox).contains(a - b)
+}
+
+// Fraction along the path of a `mover` from `start` to `end` at which it
+// first touches `target` at `center`, or None if it never does
+pub fn sweep(
+    start: Point2<f64>,
+    end: Point2<f64>,
+    mover: Hitbox,
+    center: Point2<f64>,
+    target: Hitbox,
+) -> Option<f64> {
+    let hitbox = mover.expand(&target);
+    if hitbox.contains(start - center) {
+        return Some(0.);
+    }
+
+    let path = end - start;
+    let offset = start - center;
+
+    match hitbox {
+        Hitbox::Circle { radius } => {
+            let a = path.dot(&path);
+            if a < f64::EPSILON {
+                return None;
+            }
+
+            let b = 2. * offset.dot(&path);
+            let c = offset.dot(&offset) - radius * radius;
+            let discriminant = b * b - 4. * a * c;
+            if discriminant < 0. {
+                return None;
+            }
+
+            let t = (-b - discriminant.sqrt()) / (2. * a);
+            (0. ..=1.).contains(&t).then_some(t)
+        }
+        Hitbox::Box { half_extents } => {
+            let (mut enter, mut exit) = (0_f64, 1_f64);
+            for axis in 0..2 {
+                if path[axis].abs() < f64::EPSILON {
+                    if offset[axis].abs() >= half_extents[axis] {
+                        return None;
+                    }
+                    continue;
+                }
+
+                let a = (-half_extents[axis] - offset[axis]) / path[axis];
+                let b = (half_extents[axis] - offset[axis]) / path[axis];
+                enter = enter.max(a.min(b));
+                exit = exit.min(a.max(b));
+                if enter > exit {
+                    return None;
+                }
+            }
+            Some(enter)
+        }
+    }
+}
+
+// Like `sweep`, but units that already overlap are free to move apart
+pub fn blocking(
+    start: Point2<f64>,
+    end: Point2<f64>,
+    mover: Hitbox,
+    center: Point2<f64>,
+    target: Hitbox,
+) -> Option<f64> {
+    if overlaps(start, mover, center, target) {
+        return ((end - start).dot(&(center - start)) > 0.).then_some(0.);
+    }
+    sweep(start, end, mover, center, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOT: Hitbox = Hitbox::circle(0.5);
+
+    #[test]
+    fn sweep_catches_tunneling() {
+        let t = sweep(
+            Point2::new(0., 0.),
+            Point2::new(10., 0.),
+            DOT,
+            Point2::new(5., 0.5),
+            DOT,
+        )
+        .unwrap();
+
+        assert!((t - (5. - 0.75_f64.sqrt()) / 10.).abs() < 1e-9);
+        assert_eq!(
+            sweep(
+                Point2::new(0., 0.),
+                Point2::new(10., 0.),
+                DOT,
+                Point2::new(5., 2.),
+                DOT
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn sweep_against_wide_box() {
+        let wide = Hitbox::rect(2., 0.5);
+        let path = (Point2::new(0., 0.), Point2::new(0., 10.));
+
+        let t = sweep(path.0, path.1, DOT, Point2::new(1.5, 5.), wide).unwrap();
+        assert!((t - 0.4).abs() < 1e-9);
+
+        assert_eq!(sweep(path.0, path.1, DOT, Point2::new(3., 5.), wide), None);
+    }
+
+    #[test]
+    fn blocking_lets_overlapping_units_separate() {
+        let unit = Hitbox::circle(0.6);
+        let center = Point2::new(0.5, 0.);
+
+        assert_eq!(
+            blocking(
+                Point2::new(0., 0.),
+                Point2::new(-1., 0.),
+                unit,
+                center,
+                unit
+            ),
+            None
+        );
+        assert_eq!(
+            blocking(
+                Point2::new(0., 0.),
+                Point2::new(0.2, 0.),
+                unit,
+                center,
+                unit
+            ),
+            Some(0.)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use super::stats::{Modifier, Stat};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Slot {
+    Staff,
+    Robe,
+    Amulet,
+    Ring,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Gear {
+    OakStaff,
+    SilkRobe,
+    MoonAmulet,
+    SwiftRing,
+    FocusRing,
+}
+
+pub const GEAR: [Gear; 5] = [
+    Gear::OakStaff,
+    Gear::SilkRobe,
+    Gear::MoonAmulet,
+    Gear::SwiftRing,
+    Gear::FocusRing,
+];
+
+impl Gear {
+    pub fn slot(&self) -> Slot {
+        match self {
+            Gear::OakStaff => Slot::Staff,
+            Gear::SilkRobe => Slot::Robe,
+            Gear::MoonAmulet => Slot::Amulet,
+            Gear::SwiftRing | Gear::FocusRing => Slot::Ring,
+        }
+    }
+
+    pub fn modifiers(&self) -> Vec<Modifier> {
+        match self {
+            Gear::OakStaff => vec![Modifier::Multiply(Stat::CooldownMultiplier, 0.85)],
+            Gear::SilkRobe => vec![
+                Modifier::Add(Stat::MaxEnergy, 25.),
+                Modifier::Add(Stat::Armor, 3.),
+            ],
+            Gear::MoonAmulet => vec![
+                Modifier::Multiply(Stat::RechargeDelay, 0.5),
+                Modifier::Multiply(Stat::RechargeInterval, 0.75),
+            ],
+            Gear::SwiftRing => vec![Modifier::Add(Stat::MoveSpeed, 1.5)],
+            Gear::FocusRing => vec![Modifier::Multiply(Stat::CostMultiplier, 0.8)],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Equipment {
+    staff: Option<Gear>,
+    robe: Option<Gear>,
+    amulet: Option<Gear>,
+    rings: [Option<Gear>; 2],
+}
+
+impl Equipment {
+    // Returns the piece that had to make room, if any
+    pub fn equip(&mut self, gear: Gear) -> Option<Gear> {
+        let slot = match gear.slot() {
+            Slot::Staff => &mut self.staff,
+            Slot::Robe => &mut self.robe,
+            Slot::Amulet => &mut self.amulet,
+            Slot::Ring => match self.rings.iter().position(Option::is_none) {
+                Some(ix) => &mut self.rings[ix],
+                None => {
+                    self.rings.rotate_left(1);
+                    &mut self.rings[1]
+                }
+            },
+        };
+        slot.replace(gear)
+    }
+
+    pub fn equipped(&self) -> impl Iterator<Item = Gear> + '_ {
+        [self.staff, self.robe, self.amulet]
+            .into_iter()
+            .chain(self.rings)
+            .flatten()
+    }
+
+    pub fn modifiers(&self) -> impl Iterator<Item = Modifier> + '_ {
+        self.equipped().flat_map(|gear| gear.modifiers())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::magic::Spell;
+
+use super::stats::{Modifier, Stat};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Reward {
+    Stat(Modifier),
+    Spell(Spell),
+}
+
+pub const STAT_REWARDS: [Modifier; 6] = [
+    Modifier::Add(Stat::MaxHealth, 15.),
+    Modifier::Add(Stat::MaxEnergy, 15.),
+    Modifier::Add(Stat::Armor, 2.),
+    Modifier::Add(Stat::MoveSpeed, 0.5),
+    Modifier::Multiply(Stat::RechargeInterval, 0.9),
+    Modifier::Multiply(Stat::CooldownMultiplier, 0.95),
+];
+
+impl Reward {
+    pub fn description(&self) -> String {
+        match self {
+            Reward::Stat(Modifier::Add(stat, value)) => format!("+{} {}", value, stat.name()),
+            Reward::Stat(Modifier::Multiply(stat, value)) => {
+                format!("-{:.0}% {}", (1. - value) * 100., stat.name())
+            }
+            Reward::Spell(spell) => format!("Learn {:?}: {}", spell, spell.description()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Experience {
+    pub level: u32,
+    pub xp: u32,
+    pub unspent: u32,
+}
+
+impl Experience {
+    pub fn new() -> Self {
+        Self {
+            level: 1,
+            xp: 0,
+            unspent: 0,
+        }
+    }
+
+    pub fn next_level_xp(&self) -> u32 {
+        50 + 25 * (self.level - 1)
+    }
+
+    pub fn gain(&mut self, xp: u32) {
+        self.xp += xp;
+        while self.xp >= self.next_level_xp() {
+            self.xp -= self.next_level_xp();
+            self.level += 1;
+            self.unspent += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_levels_up_on_growing_thresholds() {
+        let mut experience = Experience::new();
+
+        experience.gain(60);
+
+        assert_eq!(experience.level, 2);
+        assert_eq!(experience.xp, 10);
+        assert_eq!(experience.unspent, 1);
+
+        experience.gain(165);
+
+        assert_eq!(experience.level, 4);
+        assert_eq!(experience.xp, 0);
+        assert_eq!(experience.unspent, 3);
+    }
+}
@@ -0,0 +1,91 @@
+use nalgebra::Point2;
+
+use super::{collision::Hitbox, equipment::Gear, Entity};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Item {
+    HealthPotion,
+    EnergyPotion,
+    Gear(Gear),
+}
+
+pub const CONSUMABLES: [Item; 2] = [Item::HealthPotion, Item::EnergyPotion];
+
+pub const PICKUP_HITBOX: Hitbox = Hitbox::circle(0.6);
+
+pub struct Pickup {
+    pub location: Point2<f64>,
+    pub item: Item,
+}
+
+impl Pickup {
+    pub fn new(location: Point2<f64>, item: Item) -> Self {
+        Self { location, item }
+    }
+}
+
+impl Entity for Pickup {
+    fn location(&self) -> Point2<f64> {
+        self.location
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        PICKUP_HITBOX
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Inventory {
+    stacks: Vec<(Item, u32)>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: Item) {
+        match self.stacks.iter_mut().find(|(i, _)| *i == item) {
+            Some((_, count)) => *count += 1,
+            None => self.stacks.push((item, 1)),
+        }
+    }
+
+    pub fn take(&mut self, item: Item) -> bool {
+        let Some(ix) = self.stacks.iter().position(|(i, _)| *i == item) else {
+            return false;
+        };
+
+        self.stacks[ix].1 -= 1;
+        if self.stacks[ix].1 == 0 {
+            self.stacks.remove(ix);
+        }
+        true
+    }
+
+    pub fn count(&self, item: Item) -> u32 {
+        self.stacks
+            .iter()
+            .find(|(i, _)| *i == item)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inventory_stacks_and_empties() {
+        let mut inventory = Inventory::default();
+        assert!(!inventory.take(Item::HealthPotion));
+
+        inventory.add(Item::HealthPotion);
+        inventory.add(Item::HealthPotion);
+        inventory.add(Item::EnergyPotion);
+        assert_eq!(inventory.count(Item::HealthPotion), 2);
+        assert_eq!(inventory.count(Item::EnergyPotion), 1);
+
+        assert!(inventory.take(Item::HealthPotion));
+        assert!(inventory.take(Item::HealthPotion));
+        assert!(!inventory.take(Item::HealthPotion));
+        assert_eq!(inventory.count(Item::HealthPotion), 0);
+        assert_eq!(inventory.count(Item::EnergyPotion), 1);
+    }
+}
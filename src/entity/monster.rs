@@ -1,37 +1,140 @@
 use nalgebra::{vector, Point2};
 use rand::rngs::StdRng;
-use rand::{random, Rng, SeedableRng};
+use rand::{Rng, SeedableRng};
 
+use crate::collision::{Hitbox, MAX_UNIT_REACH};
 use crate::direction::{AsVector2, Direction};
+use crate::magic::element::{Aura, Element};
+use crate::magic::projectile::Projectile;
+use crate::magic::Spell;
+use crate::physics::Body;
 
 use super::*;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Archetype {
+    Wanderer,
+    Stalker,
+    Lurker,
+    Drifter,
+    Spitter,
+    Troll,
+    Dragon,
+}
+
+pub const ARCHETYPES: [Archetype; 7] = [
+    Archetype::Wanderer,
+    Archetype::Stalker,
+    Archetype::Lurker,
+    Archetype::Drifter,
+    Archetype::Spitter,
+    Archetype::Troll,
+    Archetype::Dragon,
+];
+
+const SPIT_RANGE: f64 = 8.;
+const SPIT_INTERVAL: u128 = 2_000;
+const SPIT_SPEED: f64 = 0.008;
+const SPIT_DAMAGE: u32 = 15;
+
+impl Archetype {
+    // Share of steps spent seeking, out of the first 100
+    fn logic(&self) -> usize {
+        match self {
+            Archetype::Wanderer => 100,
+            Archetype::Stalker => 40,
+            Archetype::Lurker => 150,
+            Archetype::Drifter => 200,
+            Archetype::Spitter => 150,
+            Archetype::Troll => 120,
+            Archetype::Dragon => 80,
+        }
+    }
+
+    fn speed(&self) -> f64 {
+        match self {
+            Archetype::Stalker => 3.,
+            Archetype::Spitter => 1.5,
+            Archetype::Troll => 1.,
+            Archetype::Dragon => 2.5,
+            _ => 2.,
+        }
+    }
+
+    pub fn health(&self) -> u32 {
+        match self {
+            Archetype::Wanderer | Archetype::Stalker | Archetype::Spitter => 2,
+            Archetype::Lurker => 3,
+            Archetype::Drifter => 1,
+            Archetype::Troll => 6,
+            Archetype::Dragon => 4,
+        }
+    }
+
+    // Drifters are tiny and slip through gaps, lurkers are bulky and trolls
+    // span several cells
+    pub fn hitbox(&self) -> Hitbox {
+        match self {
+            Archetype::Wanderer => Hitbox::circle(0.6),
+            Archetype::Stalker => Hitbox::circle(0.5),
+            Archetype::Lurker => Hitbox::rect(0.8, 0.6),
+            Archetype::Drifter => Hitbox::circle(0.3),
+            Archetype::Spitter => Hitbox::circle(0.5),
+            Archetype::Troll => Hitbox::rect(1.5, 1.),
+            Archetype::Dragon => Hitbox::circle(0.9),
+        }
+    }
+
+    pub fn xp(&self) -> u32 {
+        match self {
+            Archetype::Wanderer => 10,
+            Archetype::Stalker => 20,
+            Archetype::Lurker => 8,
+            Archetype::Drifter => 5,
+            Archetype::Spitter => 15,
+            Archetype::Troll => 40,
+            Archetype::Dragon => 30,
+        }
+    }
+}
+
 pub struct Monster {
     pub location: Point2<f64>,
+    pub archetype: Archetype,
     pub logic: usize,
     pub id: u64,
     pub speed: f64,
+    pub health: u32,
+    pub aura: Option<Aura>,
+    pub body: Body,
     last_tick: u128,
+    last_spit: Option<u128>,
 }
 
 impl Monster {
-    pub fn new_simple(location: Point2<f64>, ticker: u128) -> Self {
-        Self::new(location, ticker, None, None)
+    pub fn new_simple(location: Point2<f64>, ticker: u128, rng: &mut impl Rng) -> Self {
+        Self::new(location, ticker, Archetype::Wanderer, rng)
     }
 
+    // Ids come from the game's seeded rng so that replays spawn the same monsters
     pub fn new(
         location: Point2<f64>,
         ticker: u128,
-        logic: Option<usize>,
-        speed: Option<f64>,
+        archetype: Archetype,
+        rng: &mut impl Rng,
     ) -> Self {
-        let id = random();
+        let id = rng.gen();
         Self {
             location,
-            logic: logic.unwrap_or(100),
-            speed: speed.unwrap_or(2.) / 1000.,
+            archetype,
+            logic: archetype.logic(),
+            speed: archetype.speed() / 1000.,
+            health: archetype.health(),
             id,
+            aura: None,
+            body: Body::new(ticker),
             last_tick: ticker,
+            last_spit: None,
         }
     }
 
@@ -39,29 +142,64 @@ impl Monster {
         self.last_tick = ticker;
     }
 
+    // Returns true when the hit was fatal
+    pub fn take_hit(&mut self, damage: u32) -> bool {
+        self.health = self.health.saturating_sub(damage);
+        self.health == 0
+    }
+
+    pub fn aura(&self, ticker: u128) -> Option<Element> {
+        self.aura
+            .filter(|aura| aura.active(ticker))
+            .map(|aura| aura.element)
+    }
+
+    pub fn is_frozen(&self, ticker: u128) -> bool {
+        self.aura(ticker) == Some(Element::Frost)
+    }
+
+    // Spitters lob venom at the player from a distance
+    pub fn spit(&mut self, target: Point2<f64>, ticker: u128) -> Option<Projectile> {
+        let offset = target - self.location;
+        if self.archetype != Archetype::Spitter
+            || self.is_frozen(ticker)
+            || offset.magnitude() > SPIT_RANGE
+            || self
+                .last_spit
+                .is_some_and(|last_spit| ticker < last_spit + SPIT_INTERVAL)
+        {
+            return None;
+        }
+
+        self.last_spit = Some(ticker);
+        Some(
+            Projectile::new(Spell::Venom, SPIT_SPEED, self.location, offset, ticker)
+                .with_damage(SPIT_DAMAGE)
+                .with_hostile(true),
+        )
+    }
+
     pub fn seek(&self, seek_point: Point2<f64>, ticker: u128) -> Option<Point2<f64>> {
+        if self.is_frozen(ticker) {
+            return None;
+        }
+
         let mut rng = StdRng::seed_from_u64((ticker as u64).wrapping_add(self.id) / 2000);
 
         let step = match rng.gen::<usize>() % self.logic {
-            ..=39 => Some(seek_point - self.location),
-            ..=59 => Some(vector!(seek_point.x - self.location.x, 0.)),
-            ..=79 => Some(vector!(0., seek_point.y - self.location.y)),
-            ..=84 => Some(Direction::Right.as_vector()),
-            ..=89 => Some(Direction::Left.as_vector()),
-            ..=94 => Some(Direction::Up.as_vector()),
-            ..=99 => Some(Direction::Down.as_vector()),
+            0..=39 => Some(seek_point - self.location),
+            40..=59 => Some(vector!(seek_point.x - self.location.x, 0.)),
+            60..=79 => Some(vector!(0., seek_point.y - self.location.y)),
+            80..=84 => Some(Direction::Right.as_vector()),
+            85..=89 => Some(Direction::Left.as_vector()),
+            90..=94 => Some(Direction::Up.as_vector()),
+            95..=99 => Some(Direction::Down.as_vector()),
             _ => None,
-        };
-
-        if step.is_none() {
-            return None;
-        }
+        }?;
 
         Some(
             self.location
-                + step.unwrap().normalize()
-                    * self.speed
-                    * ticker.saturating_sub(self.last_tick) as f64,
+                + step.normalize() * self.speed * ticker.saturating_sub(self.last_tick) as f64,
         )
     }
 }
@@ -81,4 +219,85 @@ impl Entity for Monster {
     fn location(&self) -> Point2<f64> {
         self.location
     }
+
+    fn hitbox(&self) -> Hitbox {
+        let hitbox = self.archetype.hitbox();
+        debug_assert!(hitbox.reach() <= MAX_UNIT_REACH);
+        hitbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{collision::overlaps, magic::fireball::fireball, object::Object};
+
+    use super::*;
+
+    #[test]
+    fn spitters_spit_at_the_player_in_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut spitter = Monster::new(Point2::new(0., 0.), 0, Archetype::Spitter, &mut rng);
+
+        assert!(spitter.spit(Point2::new(SPIT_RANGE + 1., 0.), 0).is_none());
+        let venom = spitter.spit(Point2::new(3., 0.), 0).unwrap();
+        assert!(venom.hostile());
+        assert_eq!(venom.damage(), SPIT_DAMAGE);
+        assert!(venom.vector().x > 0.);
+        assert!(spitter
+            .spit(Point2::new(3., 0.), SPIT_INTERVAL - 1)
+            .is_none());
+        assert!(spitter.spit(Point2::new(3., 0.), SPIT_INTERVAL).is_some());
+
+        let mut wanderer = Monster::new_simple(Point2::new(0., 0.), 0, &mut rng);
+        assert!(wanderer.spit(Point2::new(3., 0.), 0).is_none());
+    }
+
+    #[test]
+    fn monsters_with_more_health_survive_a_projectile() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut shot = fireball(Point2::new(0., 0.), vector![1., 0.], 0).with_pierce(true);
+        let mut lurker = Monster::new(Point2::new(2., 0.), 0, Archetype::Lurker, &mut rng);
+        let mut wanderer = Monster::new_simple(Point2::new(4., 0.), 0, &mut rng);
+
+        assert!(shot.strike(lurker.id));
+        assert!(!lurker.take_hit(shot.damage()));
+        // Passing on through the lurker does not hurt it again
+        assert!(!shot.strike(lurker.id));
+        assert!(shot.strike(wanderer.id));
+        assert!(wanderer.take_hit(shot.damage()));
+    }
+
+    #[test]
+    fn trolls_take_several_projectiles() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut troll = Monster::new(Point2::new(2., 0.), 0, Archetype::Troll, &mut rng);
+
+        let hits = (0..)
+            .map(|ix| fireball(Point2::new(0., ix as f64), vector![1., 0.], 0))
+            .take_while(|shot| !troll.take_hit(shot.damage()))
+            .count();
+        assert_eq!(hits + 1, 3);
+    }
+
+    #[test]
+    fn archetypes_stay_within_unit_reach() {
+        for archetype in ARCHETYPES {
+            assert!(
+                archetype.hitbox().reach() <= MAX_UNIT_REACH,
+                "{:?}",
+                archetype
+            );
+        }
+
+        // Trolls block the cells on either side of them
+        let troll = Archetype::Troll.hitbox();
+        for x in [-1.5, 0., 1.5] {
+            assert!(overlaps(
+                Point2::new(5., 5.),
+                troll,
+                Point2::new(5. + x, 6.),
+                Hitbox::circle(0.4)
+            ));
+        }
+    }
 }
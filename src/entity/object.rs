@@ -1,6 +1,10 @@
 use nalgebra::{Point2, Vector2};
 
-use crate::{magic::Spell, Entity};
+use crate::{
+    collision::Hitbox,
+    magic::{element::Element, Spell},
+    Entity,
+};
 
 pub trait Object {
     fn location(&self) -> Point2<f64>;
@@ -8,10 +12,56 @@ pub trait Object {
     fn set_location(&mut self, location: Point2<f64>, ticker: u128);
     fn get_spell(&self) -> Spell;
     fn next_location(&self, ticker: u128) -> Point2<f64>;
+
+    fn element(&self) -> Option<Element> {
+        self.get_spell().element()
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::circle(0.4)
+    }
+
+    fn pierces(&self) -> bool {
+        false
+    }
+
+    fn expired(&self, _ticker: u128) -> bool {
+        false
+    }
+
+    // Tick the object enters play, emissions delayed by their pattern are
+    // held off the board until then
+    fn due(&self) -> u128 {
+        0
+    }
+
+    // Distance a surviving monster is pushed along the object's path
+    fn knockback(&self) -> f64 {
+        0.
+    }
+
+    // Thrown by monsters, hurts the player and passes monsters by
+    fn hostile(&self) -> bool {
+        false
+    }
+
+    // Health taken from whatever it hits, the player when hostile
+    fn damage(&self) -> u32 {
+        1
+    }
+
+    // Records a hit on the monster with `id`, false if it was already hit
+    fn strike(&mut self, _id: u64) -> bool {
+        true
+    }
 }
 
 impl<T: Object> Entity for T {
     fn location(&self) -> Point2<f64> {
-        self.location()
+        Object::location(self)
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Object::hitbox(self)
     }
 }
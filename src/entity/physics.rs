@@ -0,0 +1,123 @@
+use nalgebra::{Point2, Vector2};
+
+// Fraction of velocity lost per millisecond
+pub const FRICTION: f64 = 0.01;
+const REST_SPEED: f64 = 0.000_05;
+const EDGE_MARGIN: f64 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Body {
+    pub velocity: Vector2<f64>,
+    last_tick: u128,
+}
+
+impl Body {
+    pub fn new(ticker: u128) -> Self {
+        Self {
+            velocity: Vector2::zeros(),
+            last_tick: ticker,
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.velocity.magnitude() > REST_SPEED
+    }
+
+    // Sized so that friction brings the body to rest `distance` cells later
+    pub fn push(&mut self, direction: Vector2<f64>, distance: f64, ticker: u128) {
+        if let Some(direction) = direction.try_normalize(f64::EPSILON) {
+            self.absorb(direction * distance * FRICTION, ticker);
+        }
+    }
+
+    pub fn absorb(&mut self, velocity: Vector2<f64>, ticker: u128) {
+        if !self.is_moving() {
+            self.last_tick = ticker;
+        }
+        self.velocity += velocity;
+    }
+
+    pub fn stop(&mut self) -> Vector2<f64> {
+        std::mem::replace(&mut self.velocity, Vector2::zeros())
+    }
+
+    // Displacement since the last step, with friction decaying the velocity
+    pub fn step(&mut self, ticker: u128) -> Vector2<f64> {
+        let elapsed = ticker.saturating_sub(self.last_tick) as f64;
+        self.last_tick = ticker;
+
+        if !self.is_moving() {
+            self.velocity = Vector2::zeros();
+            return Vector2::zeros();
+        }
+
+        let decay = (-FRICTION * elapsed).exp();
+        let displacement = self.velocity * (1. - decay) / FRICTION;
+        self.velocity *= decay;
+        displacement
+    }
+
+    // Keeps the location inside the arena, losing velocity along blocked axes
+    pub fn confine(&mut self, location: Point2<f64>, bounds: Vector2<f64>) -> Point2<f64> {
+        let x = location.x.clamp(EDGE_MARGIN, bounds.x - EDGE_MARGIN);
+        let y = location.y.clamp(EDGE_MARGIN, bounds.y - EDGE_MARGIN);
+
+        if x != location.x {
+            self.velocity.x = 0.;
+        }
+        if y != location.y {
+            self.velocity.y = 0.;
+        }
+
+        Point2::new(x, y)
+    }
+}
+
+// Distance an explosion at `center` pushes a body at `location`, fading to
+// nothing at `radius`
+pub fn blast_distance(
+    center: Point2<f64>,
+    location: Point2<f64>,
+    radius: f64,
+    strength: f64,
+) -> f64 {
+    strength * (1. - (location - center).magnitude() / radius).max(0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::vector;
+
+    use super::*;
+
+    #[test]
+    fn push_travels_distance_before_resting() {
+        let mut body = Body::new(0);
+        body.push(vector![3., 0.], 2., 0);
+
+        let travelled: Vector2<f64> = (1..=100).map(|i| body.step(i * 10)).sum();
+
+        assert!((travelled.x - 2.).abs() < 0.01);
+        assert_eq!(travelled.y, 0.);
+        assert!(!body.is_moving());
+    }
+
+    #[test]
+    fn blasts_fade_with_distance() {
+        let center = Point2::new(5., 5.);
+        assert_eq!(blast_distance(center, center, 2., 3.), 3.);
+        assert_eq!(blast_distance(center, Point2::new(6., 5.), 2., 3.), 1.5);
+        assert_eq!(blast_distance(center, Point2::new(5., 8.), 2., 3.), 0.);
+    }
+
+    #[test]
+    fn confine_stops_blocked_axis() {
+        let mut body = Body::new(0);
+        body.velocity = vector![0.01, 0.01];
+
+        let location = body.confine(Point2::new(12., 5.), vector![10., 10.]);
+
+        assert_eq!(location, Point2::new(9.5, 5.));
+        assert_eq!(body.velocity, vector![0., 0.01]);
+    }
+}
@@ -1,78 +1,225 @@
 use std::fmt::Debug;
 
-use nalgebra::{Point2, Vector2};
+use nalgebra::{vector, Point2, Vector2};
 
 use crate::{
-    magic::{fireball::FireballMagic, inferno::InfernoMagic, sphere::SphereMagic, Magic},
-    object::Object,
+    ability::{Ability, Evocation},
+    class::Class,
+    collision::Hitbox,
+    equipment::{Equipment, Gear},
+    experience::{Experience, Reward},
+    item::{Inventory, Item},
+    magic::{
+        progression::{POINTS_PER_EVOKE, POINTS_PER_KILL},
+        resource::{Resource, COMBO_DECAY_DELAY, COMBO_DECAY_RATE, COMBO_PER_KILL, MAX_COMBO},
+        Spell,
+    },
+    physics::Body,
+    stats::{aggregate, Modifier, Stat},
     Entity, Unit,
 };
 
 pub struct Player {
     pub location: Point2<f64>,
+    pub class: Class,
+    pub facing: Vector2<f64>,
+    pub body: Body,
     pub energy: u32,
-    pub max_energy: u32,
-    pub spells: Vec<Box<dyn Magic>>,
+    pub health: u32,
+    pub combo: u32,
+    pub spells: Vec<Box<dyn Ability>>,
     pub active_spell: usize,
+    // Cast at the cursor without switching away from the active spell
+    pub secondary_spell: usize,
+    pub inventory: Inventory,
+    pub equipment: Equipment,
+    pub experience: Experience,
+    pub god_mode: bool,
+    perks: Vec<Modifier>,
     last_tick: u128,
     last_action_tick: u128,
 
     energy_recharge_tracker: u128,
+    combo_tick: u128,
+    invulnerable_until: u128,
+    last_swing: Option<u128>,
+    last_dash: Option<u128>,
 }
 
+pub const MONSTER_CONTACT_DAMAGE: u32 = 25;
+pub const CONTACT_KNOCKBACK: f64 = 1.5;
+const INVULNERABILITY: u128 = 800;
+
+pub const SWING_DAMAGE: u32 = 1;
+pub const SWING_KNOCKBACK: f64 = 2.;
+pub const SWING_RANGE: f64 = 1.5;
+// Full width of the arc in front of the player
+const SWING_ARC: f64 = 2.1;
+const SWING_COOLDOWN: u128 = 400;
+
+const DASH_DISTANCE: f64 = 3.;
+const DASH_COOLDOWN: u128 = 1500;
+const DASH_INVULNERABILITY: u128 = 250;
+
 impl Debug for Player {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Player")
             .field("location", &self.location)
+            .field("class", &self.class)
+            .field("facing", &self.facing)
+            .field("body", &self.body)
             .field("energy", &self.energy)
-            .field("max_energy", &self.max_energy)
+            .field("health", &self.health)
+            .field("combo", &self.combo)
             .field("spells.len", &self.spells.len())
             .field("active_spell", &self.active_spell)
+            .field("secondary_spell", &self.secondary_spell)
+            .field("inventory", &self.inventory)
+            .field("equipment", &self.equipment)
+            .field("experience", &self.experience)
+            .field("god_mode", &self.god_mode)
+            .field("perks", &self.perks)
             .field("last_tick", &self.last_tick)
             .field("last_action_tick", &self.last_action_tick)
             .field("energy_recharge_tracker", &self.energy_recharge_tracker)
+            .field("combo_tick", &self.combo_tick)
+            .field("invulnerable_until", &self.invulnerable_until)
+            .field("last_swing", &self.last_swing)
+            .field("last_dash", &self.last_dash)
             .finish()
     }
 }
 
 impl Player {
-    pub fn new(location: Point2<f64>, ticker: u128) -> Self {
-        Self {
+    pub fn new(location: Point2<f64>, ticker: u128, class: Class, abilities: &[Spell]) -> Self {
+        let mut player = Self {
             location,
-            energy: 100,
-            max_energy: 100,
-            spells: vec![
-                Box::new(FireballMagic::new()),
-                Box::new(SphereMagic::new()),
-                Box::new(InfernoMagic::new()),
-                Box::new(InfernoMagic::new()),
-            ],
-            active_spell: 1,
+            class,
+            facing: vector![0., -1.],
+            body: Body::new(ticker),
+            energy: 0,
+            health: 0,
+            combo: 0,
+            spells: abilities.iter().filter_map(Spell::create_ability).collect(),
+            active_spell: 0,
+            secondary_spell: usize::from(abilities.len() > 1),
+            inventory: Inventory::default(),
+            equipment: Equipment::default(),
+            experience: Experience::new(),
+            god_mode: false,
+            perks: class.modifiers(),
             last_tick: ticker,
             last_action_tick: ticker,
             energy_recharge_tracker: 0,
+            combo_tick: ticker,
+            invulnerable_until: ticker,
+            last_swing: None,
+            last_dash: None,
+        };
+        player.energy = player.max_energy();
+        player.health = player.max_health();
+        player
+    }
+
+    pub fn stat(&self, stat: Stat) -> f64 {
+        aggregate(
+            stat,
+            self.equipment.modifiers().chain(self.perks.iter().copied()),
+        )
+    }
+
+    pub fn claim_reward(&mut self, reward: Reward) {
+        self.experience.unspent = self.experience.unspent.saturating_sub(1);
+        match reward {
+            Reward::Stat(modifier) => self.perks.push(modifier),
+            Reward::Spell(spell) => self.spells.extend(spell.create_ability()),
         }
     }
 
-    #[allow(dead_code)]
-    pub fn get_active_spell(&self) -> &Box<dyn Magic> {
-        self.spells.get(self.active_spell).unwrap()
+    pub fn max_energy(&self) -> u32 {
+        self.stat(Stat::MaxEnergy).round() as u32
+    }
+
+    pub fn max_health(&self) -> u32 {
+        self.stat(Stat::MaxHealth).round() as u32
+    }
+
+    pub fn armor(&self) -> u32 {
+        self.stat(Stat::Armor).round() as u32
+    }
+
+    pub fn spell_cost(&self, spell: &dyn Ability) -> u32 {
+        (spell.cost() as f64 * self.stat(Stat::CostMultiplier)).round() as u32
     }
 
-    pub fn active_spell_evoke(
+    pub fn spell_cooldown(&self, spell: &dyn Ability) -> u128 {
+        (spell.cooldown() as f64 * self.stat(Stat::CooldownMultiplier)).round() as u128
+    }
+
+    pub fn spell_remaining_cooldown(&self, spell: &dyn Ability, ticker: u128) -> u128 {
+        spell.last_evoke().map_or(0, |last_evoke| {
+            (last_evoke + self.spell_cooldown(spell)).saturating_sub(ticker)
+        })
+    }
+
+    pub fn active_spell_evoke(&mut self, direction: Vector2<f64>, ticker: u128) -> Evocation {
+        self.spell_evoke(self.active_spell, direction, ticker)
+    }
+
+    pub fn spell_evoke(
         &mut self,
+        index: usize,
         direction: Vector2<f64>,
         ticker: u128,
-    ) -> Vec<Box<dyn Object>> {
-        let spell = &mut self.spells[self.active_spell];
-        self.energy -= spell.cost();
+    ) -> Evocation {
+        let cost = self.spell_cost(self.spells[index].as_ref());
+        let spell = &mut self.spells[index];
+        match spell.resource_mut() {
+            Resource::Energy => self.energy -= cost,
+            Resource::Charges(charges) => charges.spend(ticker),
+            Resource::Blood => self.health -= cost,
+            Resource::Combo => self.combo -= cost,
+        }
         self.last_action_tick = ticker;
+        spell.progression_mut().gain(POINTS_PER_EVOKE);
         spell.evoke(self.location, direction, ticker)
     }
 
+    pub fn reward_kill(&mut self, spell: Option<Spell>, xp: u32, ticker: u128) {
+        self.experience.gain(xp);
+
+        for magic in self
+            .spells
+            .iter_mut()
+            .filter(|m| Some(m.get_spell()) == spell)
+        {
+            magic.progression_mut().gain(POINTS_PER_KILL);
+        }
+
+        self.combo = (self.combo + COMBO_PER_KILL).min(MAX_COMBO);
+        self.combo_tick = ticker + COMBO_DECAY_DELAY;
+    }
+
+    pub fn can_afford(&self, spell: &dyn Ability, ticker: u128) -> bool {
+        let cost = self.spell_cost(spell);
+        match spell.resource() {
+            Resource::Energy => self.energy >= cost,
+            Resource::Charges(charges) => charges.available(ticker) > 0,
+            Resource::Blood => self.health > cost,
+            Resource::Combo => self.combo >= cost,
+        }
+    }
+
     pub fn active_spell_can_evoke(&self, ticker: u128) -> bool {
-        !self.spells[self.active_spell].on_cooldown(ticker)
-            && self.energy >= self.spells[self.active_spell].cost()
+        self.spell_can_evoke(self.active_spell, ticker)
+    }
+
+    pub fn spell_can_evoke(&self, index: usize, ticker: u128) -> bool {
+        let Some(spell) = self.spells.get(index) else {
+            return false;
+        };
+        self.spell_remaining_cooldown(spell.as_ref(), ticker) == 0
+            && self.can_afford(spell.as_ref(), ticker)
     }
 
     pub fn next_location(&self, vector: Vector2<f64>, ticker: u128) -> Point2<f64> {
@@ -86,25 +233,122 @@ impl Player {
     }
 
     pub fn charge_energy(&mut self, ticker: u128) {
-        if ticker.saturating_sub(self.last_action_tick) > 1_000 {
+        let delay = self.stat(Stat::RechargeDelay) as u128;
+        let interval = (self.stat(Stat::RechargeInterval) as u128).max(1);
+        let max_energy = self.max_energy();
+
+        if ticker.saturating_sub(self.last_action_tick) > delay {
             self.energy_recharge_tracker += ticker.saturating_sub(self.last_tick);
-            let stored_energy = self.energy_recharge_tracker / 200;
+            let stored_energy = self.energy_recharge_tracker / interval;
 
-            self.energy = (self.energy + stored_energy as u32).clamp(0, self.max_energy);
+            self.energy = (self.energy + stored_energy as u32).clamp(0, max_energy);
 
-            if self.energy < self.max_energy {
-                self.energy_recharge_tracker -= stored_energy * 200;
+            if self.energy < max_energy {
+                self.energy_recharge_tracker -= stored_energy * interval;
             } else {
                 self.energy_recharge_tracker = 0;
             }
         }
         self.last_tick = ticker;
     }
+
+    pub fn face(&mut self, direction: Vector2<f64>) {
+        if let Some(facing) = direction.try_normalize(f64::EPSILON) {
+            self.facing = facing;
+        }
+    }
+
+    pub fn can_swing(&self, ticker: u128) -> bool {
+        self.last_swing
+            .is_none_or(|last_swing| ticker >= last_swing + SWING_COOLDOWN)
+    }
+
+    pub fn swing(&mut self, ticker: u128) {
+        self.last_swing = Some(ticker);
+        self.last_action_tick = ticker;
+    }
+
+    // Glides the player along the facing direction, briefly untouchable
+    pub fn dash(&mut self, ticker: u128) -> bool {
+        if self
+            .last_dash
+            .is_some_and(|last_dash| ticker < last_dash + DASH_COOLDOWN)
+        {
+            return false;
+        }
+
+        self.last_dash = Some(ticker);
+        self.body.push(self.facing, DASH_DISTANCE, ticker);
+        self.invulnerable_until = self.invulnerable_until.max(ticker + DASH_INVULNERABILITY);
+        true
+    }
+
+    pub fn in_swing_arc(&self, target: Point2<f64>, hitbox: Hitbox) -> bool {
+        let offset = target - self.location;
+        let distance = offset.magnitude();
+        if distance - hitbox.reach() >= SWING_RANGE {
+            return false;
+        }
+
+        distance < f64::EPSILON || self.facing.angle(&offset) <= SWING_ARC / 2.
+    }
+
+    pub fn take_damage(&mut self, amount: u32, ticker: u128) -> bool {
+        if self.god_mode || ticker < self.invulnerable_until {
+            return false;
+        }
+
+        let damage = amount.saturating_sub(self.armor()).max(1);
+        self.health = self.health.saturating_sub(damage);
+        self.invulnerable_until = ticker + INVULNERABILITY;
+        true
+    }
+
+    // Returns the gear that was swapped out, if any
+    pub fn pick_up(&mut self, item: Item) -> Option<Gear> {
+        match item {
+            Item::Gear(gear) => {
+                let old = self.equipment.equip(gear);
+                self.energy = self.energy.min(self.max_energy());
+                self.health = self.health.min(self.max_health());
+                old
+            }
+            _ => {
+                self.inventory.add(item);
+                None
+            }
+        }
+    }
+
+    pub fn use_item(&mut self, item: Item) -> bool {
+        if !self.inventory.take(item) {
+            return false;
+        }
+
+        match item {
+            Item::HealthPotion => self.health = (self.health + 40).min(self.max_health()),
+            Item::EnergyPotion => self.energy = (self.energy + 50).min(self.max_energy()),
+            Item::Gear(_) => {}
+        }
+        true
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health == 0
+    }
+
+    pub fn decay_combo(&mut self, ticker: u128) {
+        if ticker > self.combo_tick {
+            let steps = (ticker - self.combo_tick) / COMBO_DECAY_RATE;
+            self.combo = self.combo.saturating_sub(steps as u32);
+            self.combo_tick += steps * COMBO_DECAY_RATE;
+        }
+    }
 }
 
 impl Unit for Player {
     fn speed(&self) -> f64 {
-        5.
+        self.stat(Stat::MoveSpeed)
     }
 
     fn set_location(&mut self, location: Point2<f64>, ticker: u128) {
@@ -118,4 +362,165 @@ impl Entity for Player {
     fn location(&self) -> Point2<f64> {
         self.location
     }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::circle(0.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swing_arc_covers_facing_side_only() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Warrior, &[]);
+        player.face(vector![1., 0.]);
+
+        let dot = Hitbox::circle(0.);
+        assert!(player.in_swing_arc(Point2::new(6., 5.), dot));
+        assert!(player.in_swing_arc(Point2::new(6., 6.), dot));
+        assert!(!player.in_swing_arc(Point2::new(4., 5.), dot));
+        assert!(!player.in_swing_arc(Point2::new(7., 5.), dot));
+        assert!(player.in_swing_arc(Point2::new(7., 5.), Hitbox::circle(0.6)));
+    }
+
+    #[test]
+    fn armor_soaks_damage_then_hits_leave_the_player_untouchable() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Warrior, &[]);
+        let health = player.health;
+        let armor = player.armor();
+        assert!(armor > 0);
+
+        assert!(player.take_damage(25, 100));
+        assert_eq!(player.health, health - (25 - armor));
+        assert!(!player.take_damage(25, 100 + INVULNERABILITY - 1));
+        assert_eq!(player.health, health - (25 - armor));
+
+        // Armor never soaks a hit entirely
+        assert!(player.take_damage(1, 100 + INVULNERABILITY));
+        assert_eq!(player.health, health - (25 - armor) - 1);
+    }
+
+    #[test]
+    fn damage_kills_only_outside_god_mode() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Wizard, &[]);
+        player.god_mode = true;
+        assert!(!player.take_damage(u32::MAX, 0));
+        assert!(!player.is_dead());
+
+        player.god_mode = false;
+        assert!(player.take_damage(u32::MAX, 0));
+        assert_eq!(player.health, 0);
+        assert!(player.is_dead());
+    }
+
+    #[test]
+    fn pickups_stack_potions_and_equip_gear() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Wizard, &[]);
+        let max_energy = player.max_energy();
+
+        player.pick_up(Item::HealthPotion);
+        player.pick_up(Item::Gear(Gear::SilkRobe));
+        assert_eq!(player.inventory.count(Item::HealthPotion), 1);
+        assert_eq!(player.inventory.count(Item::Gear(Gear::SilkRobe)), 0);
+        assert!(player.max_energy() > max_energy);
+
+        player.health = 10;
+        assert!(player.use_item(Item::HealthPotion));
+        assert_eq!(player.health, 50);
+        assert!(!player.use_item(Item::HealthPotion));
+
+        player.health = player.max_health();
+        player.pick_up(Item::HealthPotion);
+        assert!(player.use_item(Item::HealthPotion));
+        assert_eq!(player.health, player.max_health());
+    }
+
+    #[test]
+    fn gear_in_a_full_slot_is_swapped_out() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Wizard, &[]);
+
+        assert_eq!(player.pick_up(Item::HealthPotion), None);
+        assert_eq!(player.pick_up(Item::Gear(Gear::OakStaff)), None);
+        assert_eq!(
+            player.pick_up(Item::Gear(Gear::OakStaff)),
+            Some(Gear::OakStaff)
+        );
+
+        // The oldest ring makes room once both are taken
+        assert_eq!(player.pick_up(Item::Gear(Gear::SwiftRing)), None);
+        assert_eq!(player.pick_up(Item::Gear(Gear::FocusRing)), None);
+        assert_eq!(
+            player.pick_up(Item::Gear(Gear::FocusRing)),
+            Some(Gear::SwiftRing)
+        );
+    }
+
+    #[test]
+    fn dash_glides_untouchable_then_cools_down() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Wizard, &[]);
+        player.face(vector![1., 0.]);
+
+        assert!(player.dash(100));
+        assert!(player.body.is_moving());
+        assert!(!player.take_damage(MONSTER_CONTACT_DAMAGE, 100 + DASH_INVULNERABILITY - 1));
+        assert!(!player.dash(100 + DASH_COOLDOWN - 1));
+        assert!(player.dash(100 + DASH_COOLDOWN));
+    }
+
+    #[test]
+    fn secondary_spell_casts_on_its_own_resource_and_cooldown() {
+        let mut player = Player::new(
+            Point2::new(5., 5.),
+            0,
+            Class::Wizard,
+            &[Spell::Fireball, Spell::Sphere],
+        );
+        assert_eq!(player.secondary_spell, 1);
+
+        let energy = player.energy;
+        let evocation = player.spell_evoke(player.secondary_spell, vector![0., -1.], 0);
+        assert!(!evocation.objects.is_empty());
+        assert!(evocation
+            .objects
+            .iter()
+            .all(|object| object.get_spell() == Spell::Sphere));
+        assert_eq!(
+            player.energy,
+            energy - player.spell_cost(player.spells[1].as_ref())
+        );
+        assert!(!player.spell_can_evoke(player.secondary_spell, 1));
+        assert!(player.active_spell_can_evoke(1));
+
+        let single = Player::new(Point2::new(5., 5.), 0, Class::Wizard, &[Spell::Fireball]);
+        assert_eq!(single.secondary_spell, single.active_spell);
+    }
+
+    #[test]
+    fn summons_raise_allies_instead_of_objects() {
+        let mut player = Player::new(Point2::new(5., 5.), 0, Class::Necromancer, &[Spell::Summon]);
+        let evocation = player.active_spell_evoke(vector![1., 0.], 0);
+        assert!(evocation.objects.is_empty());
+        assert_eq!(evocation.allies.len(), 1);
+        assert!(evocation.allies[0].location.x > 5.);
+    }
+
+    #[test]
+    fn kills_progress_every_copy_of_the_spell() {
+        let mut player = Player::new(
+            Point2::new(5., 5.),
+            0,
+            Class::Wizard,
+            &[Spell::Fireball, Spell::Fireball, Spell::Sphere],
+        );
+        player.reward_kill(Some(Spell::Fireball), 1, 0);
+
+        let points: Vec<_> = player
+            .spells
+            .iter()
+            .map(|spell| spell.progression().points)
+            .collect();
+        assert_eq!(points, vec![POINTS_PER_KILL, POINTS_PER_KILL, 0]);
+    }
 }
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use nalgebra::{vector, Point2};
+
+type Cell = (i64, i64);
+
+// Uniform grid bucketing entries by the cell their location falls in, so
+// proximity queries only look at the few cells overlapping the search radius
+pub struct SpatialGrid<K> {
+    cell_size: f64,
+    cells: HashMap<Cell, Vec<(K, Point2<f64>)>>,
+}
+
+impl<K: Copy + PartialEq> SpatialGrid<K> {
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn build(cell_size: f64, entries: impl IntoIterator<Item = (K, Point2<f64>)>) -> Self {
+        let mut grid = Self::new(cell_size);
+        for (key, location) in entries {
+            grid.insert(key, location);
+        }
+        grid
+    }
+
+    fn cell(&self, location: Point2<f64>) -> Cell {
+        (
+            (location.x / self.cell_size).floor() as i64,
+            (location.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    pub fn insert(&mut self, key: K, location: Point2<f64>) {
+        let cell = self.cell(location);
+        self.cells.entry(cell).or_default().push((key, location));
+    }
+
+    pub fn remove(&mut self, key: K, location: Point2<f64>) {
+        let cell = self.cell(location);
+        if let Some(entries) = self.cells.get_mut(&cell) {
+            entries.retain(|(k, _)| *k != key);
+            if entries.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    pub fn relocate(&mut self, key: K, old: Point2<f64>, new: Point2<f64>) {
+        self.remove(key, old);
+        self.insert(key, new);
+    }
+
+    // Files the entry at `location` under another key
+    pub fn relocate_key(&mut self, old: K, new: K, location: Point2<f64>) {
+        self.remove(old, location);
+        self.insert(new, location);
+    }
+
+    // Candidates for a sweep from `start` to `end`, not filtered by distance to the path
+    pub fn along(
+        &self,
+        start: Point2<f64>,
+        end: Point2<f64>,
+        radius: f64,
+    ) -> Vec<(K, Point2<f64>)> {
+        let half = (end - start) / 2.;
+        self.nearby(start + half, half.magnitude() + radius)
+    }
+
+    // Entries strictly closer than `radius` to `center`
+    pub fn nearby(&self, center: Point2<f64>, radius: f64) -> Vec<(K, Point2<f64>)> {
+        let (min_x, min_y) = self.cell(center - vector![radius, radius]);
+        let (max_x, max_y) = self.cell(center + vector![radius, radius]);
+
+        let mut found = Vec::new();
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                let Some(entries) = self.cells.get(&(x, y)) else {
+                    continue;
+                };
+                found.extend(
+                    entries
+                        .iter()
+                        .filter(|(_, location)| (location - center).magnitude() < radius),
+                );
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearby_spans_cell_borders() {
+        let grid = SpatialGrid::build(
+            2.,
+            [
+                (1, Point2::new(1.9, 1.9)),
+                (2, Point2::new(2.1, 2.1)),
+                (3, Point2::new(5., 5.)),
+            ],
+        );
+
+        let mut found: Vec<_> = grid
+            .nearby(Point2::new(2., 2.), 1.)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        found.sort();
+
+        assert_eq!(found, vec![1, 2]);
+    }
+
+    #[test]
+    fn relocate_moves_between_cells() {
+        let mut grid = SpatialGrid::build(2., [(7, Point2::new(0.5, 0.5))]);
+
+        grid.relocate(7, Point2::new(0.5, 0.5), Point2::new(9.5, 9.5));
+
+        assert!(grid.nearby(Point2::new(0.5, 0.5), 1.).is_empty());
+        assert_eq!(grid.nearby(Point2::new(9., 9.), 1.).len(), 1);
+    }
+
+    #[test]
+    fn relocate_key_keeps_the_location() {
+        let mut grid = SpatialGrid::build(2., [(0, Point2::new(1., 1.)), (1, Point2::new(4., 4.))]);
+
+        grid.relocate_key(1, 0, Point2::new(4., 4.));
+
+        assert_eq!(
+            grid.nearby(Point2::new(4., 4.), 1.),
+            vec![(0, Point2::new(4., 4.))]
+        );
+    }
+}
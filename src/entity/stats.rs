@@ -0,0 +1,76 @@
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Stat {
+    MoveSpeed,
+    MaxHealth,
+    MaxEnergy,
+    Armor,
+    RechargeDelay,
+    RechargeInterval,
+    CostMultiplier,
+    CooldownMultiplier,
+}
+
+impl Stat {
+    pub fn base(&self) -> f64 {
+        match self {
+            Stat::MoveSpeed => 5.,
+            Stat::MaxHealth => 100.,
+            Stat::MaxEnergy => 100.,
+            Stat::Armor => 5.,
+            Stat::RechargeDelay => 1_000.,
+            Stat::RechargeInterval => 200.,
+            Stat::CostMultiplier => 1.,
+            Stat::CooldownMultiplier => 1.,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::MoveSpeed => "move speed",
+            Stat::MaxHealth => "max health",
+            Stat::MaxEnergy => "max energy",
+            Stat::Armor => "armor",
+            Stat::RechargeDelay => "recharge delay",
+            Stat::RechargeInterval => "recharge interval",
+            Stat::CostMultiplier => "spell cost",
+            Stat::CooldownMultiplier => "spell cooldown",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Modifier {
+    Add(Stat, f64),
+    Multiply(Stat, f64),
+}
+
+// Additive modifiers are summed onto the base before multipliers are applied
+pub fn aggregate(stat: Stat, modifiers: impl IntoIterator<Item = Modifier>) -> f64 {
+    let (add, multiply) = modifiers
+        .into_iter()
+        .fold((0., 1.), |(add, multiply), modifier| match modifier {
+            Modifier::Add(s, value) if s == stat => (add + value, multiply),
+            Modifier::Multiply(s, value) if s == stat => (add, multiply * value),
+            _ => (add, multiply),
+        });
+
+    ((stat.base() + add) * multiply).max(0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_adds_before_multiplying() {
+        let modifiers = [
+            Modifier::Multiply(Stat::MoveSpeed, 2.),
+            Modifier::Add(Stat::MoveSpeed, 1.),
+            Modifier::Add(Stat::MaxEnergy, 50.),
+        ];
+
+        assert_eq!(aggregate(Stat::MoveSpeed, modifiers), 12.);
+        assert_eq!(aggregate(Stat::MaxEnergy, modifiers), 150.);
+        assert_eq!(aggregate(Stat::Armor, modifiers), 5.);
+    }
+}
@@ -0,0 +1,154 @@
+use std::{fs, io};
+
+use log::warn;
+
+use crate::{
+    command::Command,
+    console::{AsSymbol, Keymap},
+    display::Display,
+    magic::{Spell, KNOWN_SPELLS},
+    menu,
+};
+
+pub const MAX_LOADOUT: usize = 4;
+pub const DEFAULT_LOADOUT: [Spell; 4] = [
+    Spell::Fireball,
+    Spell::Sphere,
+    Spell::Inferno,
+    Spell::Inferno,
+];
+
+const LOADOUT_FILE: &str = "rust_dungeon.loadout";
+
+pub fn load() -> Option<Vec<Spell>> {
+    let content = fs::read_to_string(LOADOUT_FILE).ok()?;
+    parse(&content)
+        .map_err(|err| warn!("Ignoring {}: {}", LOADOUT_FILE, err))
+        .ok()
+}
+
+pub fn save(spells: &[Spell]) -> io::Result<()> {
+    fs::write(LOADOUT_FILE, serialize(spells))
+}
+
+fn parse(content: &str) -> Result<Vec<Spell>, String> {
+    let spells = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<Spell>, _>>()?;
+
+    match spells.len() {
+        0 => Err(String::from("no spells")),
+        len if len > MAX_LOADOUT => Err(format!("{} spells, at most {}", len, MAX_LOADOUT)),
+        _ => Ok(spells),
+    }
+}
+
+fn serialize(spells: &[Spell]) -> String {
+    spells
+        .iter()
+        .map(|spell| format!("{:?}\n", spell))
+        .collect()
+}
+
+fn summary(spells: &[Spell]) -> String {
+    spells
+        .iter()
+        .enumerate()
+        .map(|(i, spell)| format!("{}:{}", i + 1, spell.as_symbol()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn choose_loadout(display: &mut impl Display, keymap: &Keymap) -> io::Result<Vec<Spell>> {
+    let last = load().unwrap_or_else(|| DEFAULT_LOADOUT.to_vec());
+
+    let start = [
+        format!("Last loadout  {}", summary(&last)),
+        String::from("Choose new loadout"),
+    ];
+    if menu::choose(display, keymap, "Spellbook", &start)? != Some(1) {
+        return Ok(last);
+    }
+
+    let options: Vec<String> = KNOWN_SPELLS
+        .iter()
+        .filter_map(|spell| {
+            let magic = spell.create_ability()?;
+            Some(format!(
+                "{} {:<8} {:>2}e {:>4.1}s  {}",
+                spell.as_symbol(),
+                format!("{:?}", spell),
+                magic.cost(),
+                magic.cooldown() as f64 / 1000.,
+                spell.description()
+            ))
+        })
+        .collect();
+
+    // Any key can be reassigned, an empty key can be added after the last
+    let mut loadout = last;
+    loop {
+        let keys: Vec<String> = (0..MAX_LOADOUT.min(loadout.len() + 1))
+            .map(|ix| match loadout.get(ix) {
+                Some(spell) => format!(
+                    "Key {}  {} {:?}",
+                    keymap.label(Command::SelectSpell(ix)),
+                    spell.as_symbol(),
+                    spell
+                ),
+                None => format!("Key {}  -", keymap.label(Command::SelectSpell(ix))),
+            })
+            .collect();
+        let title = format!(
+            "Assign which key  ({} when done)",
+            keymap.label(Command::Quit)
+        );
+        let Some(key) = menu::choose(display, keymap, &title, &keys)? else {
+            break;
+        };
+
+        let title = format!(
+            "Spell for key {}  ({} to keep)",
+            keymap.label(Command::SelectSpell(key)),
+            keymap.label(Command::Quit)
+        );
+        if let Some(ix) = menu::choose(display, keymap, &title, &options)? {
+            match loadout.get_mut(key) {
+                Some(spell) => *spell = KNOWN_SPELLS[ix],
+                None => loadout.push(KNOWN_SPELLS[ix]),
+            }
+        }
+    }
+
+    if let Err(err) = save(&loadout) {
+        warn!("Could not save {}: {}", LOADOUT_FILE, err);
+    }
+
+    Ok(loadout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_loadouts_parse_back() {
+        let spells = vec![Spell::Orb, Spell::Fireball, Spell::Orb];
+        assert_eq!(parse(&serialize(&spells)), Ok(spells));
+        assert_eq!(
+            parse(" spark \n\nsphere\n"),
+            Ok(vec![Spell::Spark, Spell::Sphere])
+        );
+    }
+
+    #[test]
+    fn invalid_loadouts_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("Fireball\nMeteor\n").is_err());
+        assert!(parse(&serialize(&[Spell::Fireball; MAX_LOADOUT + 1])).is_err());
+        // Class abilities are not part of the spellbook
+        assert!(parse("Arrow\n").is_err());
+    }
+}
@@ -1,28 +1,96 @@
-use nalgebra::{Point2, Vector2};
+use std::str::FromStr;
 
-use crate::object::Object;
+use crate::ability::{arrow::ArrowAbility, summon::SummonAbility, sweep::SweepAbility, Ability};
 
+use self::element::Element;
+use self::fireball::FireballAbility;
+use self::inferno::InfernoAbility;
+use self::orb::OrbAbility;
+use self::spark::SparkAbility;
+use self::sphere::SphereAbility;
+
+pub mod element;
+pub mod emission;
 pub mod fireball;
 pub mod inferno;
+pub mod orb;
+pub mod progression;
+pub mod projectile;
+pub mod resource;
+pub mod spark;
 pub mod sphere;
+pub mod steam;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Spell {
     Fireball,
     Sphere,
     Inferno,
+    Spark,
+    Orb,
+    Steam,
+    Arrow,
+    Sweep,
+    Summon,
+    Venom,
 }
 
-pub trait Magic {
-    fn cost(&self) -> u32;
-    fn cooldown(&self) -> u128;
-    fn remaining_cooldown(&self, ticker: u128) -> u128;
-    fn on_cooldown(&self, ticker: u128) -> bool;
-    fn evoke(
-        &mut self,
-        location: Point2<f64>,
-        direction: Vector2<f64>,
-        ticker: u128,
-    ) -> Vec<Box<dyn Object>>;
-    fn get_spell(&self) -> Spell;
+pub const KNOWN_SPELLS: [Spell; 5] = [
+    Spell::Fireball,
+    Spell::Sphere,
+    Spell::Inferno,
+    Spell::Spark,
+    Spell::Orb,
+];
+
+impl Spell {
+    pub fn element(&self) -> Option<Element> {
+        match self {
+            Spell::Fireball | Spell::Inferno | Spell::Steam => Some(Element::Fire),
+            Spell::Sphere => Some(Element::Frost),
+            Spell::Spark => Some(Element::Lightning),
+            Spell::Orb => Some(Element::Arcane),
+            Spell::Arrow | Spell::Sweep | Spell::Summon | Spell::Venom => None,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Spell::Fireball => "burns, 3 charges",
+            Spell::Sphere => "freezes monsters in place",
+            Spell::Inferno => "ring of fire, costs combo",
+            Spell::Spark => "fast bolt paid in blood",
+            Spell::Orb => "slow orb, dispels elements",
+            Spell::Steam => "scalding cloud",
+            Spell::Arrow => "swift arrow, refills from a quiver",
+            Spell::Sweep => "short melee arc",
+            Spell::Summon => "raises a wraith paid in blood",
+            Spell::Venom => "spat by monsters",
+        }
+    }
+
+    pub fn create_ability(&self) -> Option<Box<dyn Ability>> {
+        match self {
+            Spell::Fireball => Some(Box::new(FireballAbility::new())),
+            Spell::Sphere => Some(Box::new(SphereAbility::new())),
+            Spell::Inferno => Some(Box::new(InfernoAbility::new())),
+            Spell::Spark => Some(Box::new(SparkAbility::new())),
+            Spell::Orb => Some(Box::new(OrbAbility::new())),
+            Spell::Arrow => Some(Box::new(ArrowAbility::new())),
+            Spell::Sweep => Some(Box::new(SweepAbility::new())),
+            Spell::Summon => Some(Box::new(SummonAbility::new())),
+            Spell::Steam | Spell::Venom => None,
+        }
+    }
+}
+
+impl FromStr for Spell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KNOWN_SPELLS
+            .into_iter()
+            .find(|spell| format!("{:?}", spell).eq_ignore_ascii_case(s.trim()))
+            .ok_or(format!("unknown spell '{}'", s.trim()))
+    }
 }
@@ -0,0 +1,113 @@
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Element {
+    Fire,
+    Frost,
+    Lightning,
+    Arcane,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Combination {
+    Steam,
+    Nullify,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Impact {
+    Kill,
+    Freeze,
+    Shatter,
+    Dispel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Aura {
+    pub element: Element,
+    pub until: u128,
+}
+
+impl Aura {
+    pub fn new(element: Element, ticker: u128, duration: u128) -> Self {
+        Self {
+            element,
+            until: ticker + duration,
+        }
+    }
+
+    pub fn active(&self, ticker: u128) -> bool {
+        ticker < self.until
+    }
+}
+
+pub const FROZEN_DURATION: u128 = 3_000;
+
+// Two projectiles meeting in flight. Lookup is symmetric.
+const COMBINATIONS: [(Element, Element, Combination); 4] = [
+    (Element::Fire, Element::Frost, Combination::Steam),
+    (Element::Arcane, Element::Fire, Combination::Nullify),
+    (Element::Arcane, Element::Frost, Combination::Nullify),
+    (Element::Arcane, Element::Lightning, Combination::Nullify),
+];
+
+// A projectile hitting a monster, keyed on the aura the monster carries.
+// `None` is the fallback when no aura specific rule exists.
+const IMPACTS: [(Element, Option<Element>, Impact); 7] = [
+    (Element::Fire, None, Impact::Kill),
+    (Element::Fire, Some(Element::Frost), Impact::Shatter),
+    (Element::Frost, None, Impact::Freeze),
+    (Element::Lightning, None, Impact::Kill),
+    (Element::Lightning, Some(Element::Frost), Impact::Shatter),
+    (Element::Arcane, None, Impact::Kill),
+    (Element::Arcane, Some(Element::Frost), Impact::Dispel),
+];
+
+pub fn combine(first: Element, second: Element) -> Option<Combination> {
+    COMBINATIONS
+        .iter()
+        .find(|(a, b, _)| (*a == first && *b == second) || (*a == second && *b == first))
+        .map(|(_, _, outcome)| *outcome)
+}
+
+pub fn impact(element: Element, aura: Option<Element>) -> Impact {
+    IMPACTS
+        .iter()
+        .find(|(e, a, _)| *e == element && aura.is_some() && *a == aura)
+        .or_else(|| {
+            IMPACTS
+                .iter()
+                .find(|(e, a, _)| *e == element && a.is_none())
+        })
+        .map(|(_, _, outcome)| *outcome)
+        .unwrap_or(Impact::Kill)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_is_symmetric() {
+        assert_eq!(
+            combine(Element::Fire, Element::Frost),
+            Some(Combination::Steam)
+        );
+        assert_eq!(
+            combine(Element::Frost, Element::Fire),
+            Some(Combination::Steam)
+        );
+        assert_eq!(combine(Element::Fire, Element::Fire), None);
+    }
+
+    #[test]
+    fn impact_prefers_aura_rule() {
+        assert_eq!(impact(Element::Fire, None), Impact::Kill);
+        assert_eq!(impact(Element::Fire, Some(Element::Frost)), Impact::Shatter);
+        assert_eq!(impact(Element::Frost, None), Impact::Freeze);
+    }
+
+    #[test]
+    fn impact_falls_back_without_aura_rule() {
+        assert_eq!(impact(Element::Frost, Some(Element::Frost)), Impact::Freeze);
+        assert_eq!(impact(Element::Fire, Some(Element::Fire)), Impact::Kill);
+    }
+}
@@ -0,0 +1,222 @@
+use std::f64::consts::TAU;
+
+use nalgebra::{vector, Point2, Rotation2, Vector2};
+
+use crate::Object;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Emission {
+    pub offset: Vector2<f64>,
+    pub direction: Vector2<f64>,
+    pub delay: u128,
+}
+
+// All patterns are laid out relative to the aim, which points from the
+// caster at the target
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Pattern {
+    Ring {
+        count: u32,
+        radius: f64,
+    },
+    Cone {
+        count: u32,
+        angle: f64,
+    },
+    Spiral {
+        count: u32,
+        turns: f64,
+        interval: u128,
+    },
+    // Around the target the caster aims at
+    Burst {
+        count: u32,
+    },
+    Wall {
+        count: u32,
+        spacing: f64,
+    },
+}
+
+impl Pattern {
+    pub fn emit(&self, aim: Vector2<f64>) -> Vec<Emission> {
+        let direction = aim.try_normalize(f64::EPSILON).unwrap_or(vector![0., -1.]);
+        let rotated = |angle: f64| Rotation2::new(angle) * direction;
+
+        match *self {
+            Pattern::Ring { count, radius } => (0..count)
+                .map(|i| {
+                    let direction = rotated(TAU * i as f64 / count as f64);
+                    Emission {
+                        offset: direction * radius,
+                        direction,
+                        delay: 0,
+                    }
+                })
+                .collect(),
+            Pattern::Cone { count, angle } => (0..count)
+                .map(|i| {
+                    let direction = rotated(spaced(i, count) * angle);
+                    Emission {
+                        offset: direction,
+                        direction,
+                        delay: 0,
+                    }
+                })
+                .collect(),
+            Pattern::Spiral {
+                count,
+                turns,
+                interval,
+            } => (0..count)
+                .map(|i| {
+                    let direction = rotated(TAU * turns * i as f64 / count as f64);
+                    Emission {
+                        offset: direction,
+                        direction,
+                        delay: i as u128 * interval,
+                    }
+                })
+                .collect(),
+            Pattern::Burst { count } => {
+                if count == 1 {
+                    return vec![Emission {
+                        offset: aim,
+                        direction,
+                        delay: 0,
+                    }];
+                }
+                (0..count)
+                    .map(|i| {
+                        let direction = rotated(TAU * i as f64 / count as f64);
+                        Emission {
+                            offset: aim + direction * 0.5,
+                            direction,
+                            delay: 0,
+                        }
+                    })
+                    .collect()
+            }
+            Pattern::Wall { count, spacing } => {
+                let across = vector![-direction.y, direction.x];
+                let middle = (count as f64 - 1.) / 2.;
+                (0..count)
+                    .map(|i| Emission {
+                        offset: direction + across * spacing * (i as f64 - middle),
+                        direction,
+                        delay: 0,
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+// Position of `i` in `count` evenly spaced slots over [-0.5, 0.5]
+fn spaced(i: u32, count: u32) -> f64 {
+    if count <= 1 {
+        0.
+    } else {
+        i as f64 / (count - 1) as f64 - 0.5
+    }
+}
+
+pub fn compose(patterns: &[Pattern], aim: Vector2<f64>) -> Vec<Emission> {
+    patterns
+        .iter()
+        .flat_map(|pattern| pattern.emit(aim))
+        .collect()
+}
+
+// One object per emission, `create` is given where it starts, its heading
+// and the tick it is due
+pub fn launch<T: Object + 'static>(
+    emissions: Vec<Emission>,
+    location: Point2<f64>,
+    ticker: u128,
+    create: impl Fn(Point2<f64>, Vector2<f64>, u128) -> T,
+) -> Vec<Box<dyn Object>> {
+    emissions
+        .into_iter()
+        .map(|emission| {
+            Box::new(create(
+                location + emission.offset,
+                emission.direction,
+                ticker + emission.delay,
+            )) as Box<dyn Object>
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn ring_starts_along_direction() {
+        let emissions = Pattern::Ring {
+            count: 4,
+            radius: 2.,
+        }
+        .emit(vector![1., 0.]);
+
+        assert_eq!(emissions.len(), 4);
+        assert!((emissions[0].offset - vector![2., 0.]).magnitude() < EPSILON);
+        assert!((emissions[2].direction - vector![-1., 0.]).magnitude() < EPSILON);
+    }
+
+    #[test]
+    fn cone_is_symmetric_around_direction() {
+        let emissions = Pattern::Cone {
+            count: 3,
+            angle: 0.4,
+        }
+        .emit(vector![0., 1.]);
+
+        assert!((emissions[1].direction - vector![0., 1.]).magnitude() < EPSILON);
+        assert!((emissions[0].direction.x + emissions[2].direction.x).abs() < EPSILON);
+    }
+
+    #[test]
+    fn wall_is_perpendicular_to_direction() {
+        let emissions = Pattern::Wall {
+            count: 3,
+            spacing: 1.,
+        }
+        .emit(vector![0., -1.]);
+
+        let offsets: Vec<_> = emissions.iter().map(|e| e.offset).collect();
+        assert!((offsets[0] - vector![-1., -1.]).magnitude() < EPSILON);
+        assert!((offsets[1] - vector![0., -1.]).magnitude() < EPSILON);
+        assert!((offsets[2] - vector![1., -1.]).magnitude() < EPSILON);
+    }
+
+    #[test]
+    fn burst_is_centered_on_the_target() {
+        let target = vector![3., 4.];
+        let single = Pattern::Burst { count: 1 }.emit(target);
+        assert!((single[0].offset - target).magnitude() < EPSILON);
+
+        let emissions = Pattern::Burst { count: 4 }.emit(target);
+        let center = emissions.iter().map(|e| e.offset).sum::<Vector2<f64>>() / 4.;
+        assert!((center - target).magnitude() < EPSILON);
+        assert!((emissions[0].direction - vector![0.6, 0.8]).magnitude() < EPSILON);
+    }
+
+    #[test]
+    fn spiral_delays_each_emission() {
+        let emissions = compose(
+            &[Pattern::Spiral {
+                count: 3,
+                turns: 1.,
+                interval: 50,
+            }],
+            vector![1., 0.],
+        );
+
+        let delays: Vec<_> = emissions.iter().map(|e| e.delay).collect();
+        assert_eq!(delays, vec![0, 50, 100]);
+    }
+}
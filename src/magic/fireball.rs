@@ -1,97 +1,88 @@
 use nalgebra::{Point2, Vector2};
 
 use crate::{
-    magic::{Magic, Spell},
-    Object,
+    ability::{Ability, Evocation},
+    magic::{
+        emission::{compose, launch, Pattern},
+        progression::Progression,
+        projectile::Projectile,
+        resource::{Charges, Resource},
+        Spell,
+    },
 };
 
-#[derive(Debug)]
-pub struct FireballObject {
-    pub location: Point2<f64>,
-    pub vector: Vector2<f64>,
-    last_tick: u128,
-}
-
-impl FireballObject {
-    pub fn new(location: Point2<f64>, vector: Vector2<f64>, ticker: u128) -> Self {
-        Self {
-            location,
-            vector: vector.normalize() * 0.01,
-            last_tick: ticker,
-        }
-    }
-}
-
-impl Object for FireballObject {
-    fn location(&self) -> Point2<f64> {
-        self.location
-    }
-
-    fn vector(&self) -> Vector2<f64> {
-        self.vector
-    }
-
-    fn set_location(&mut self, location: Point2<f64>, ticker: u128) {
-        self.location = location;
-        self.last_tick = ticker;
-    }
-
-    fn get_spell(&self) -> Spell {
-        Spell::Fireball
-    }
-
-    fn next_location(&self, ticker: u128) -> Point2<f64> {
-        self.location + self.vector * ticker.saturating_sub(self.last_tick) as f64
-    }
+pub fn fireball(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Projectile {
+    Projectile::new(Spell::Fireball, 0.01, location, direction, ticker).with_damage(2)
 }
 
 #[derive(Debug)]
-pub struct FireballMagic {
+pub struct FireballAbility {
     last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
 }
 
-impl FireballMagic {
+impl FireballAbility {
     pub fn new() -> Self {
-        Self { last_evoke: None }
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Charges(Charges::new(3, 2_500)),
+        }
     }
 }
 
-impl Magic for FireballMagic {
+impl Ability for FireballAbility {
     fn cost(&self) -> u32 {
-        10
+        self.progression.cost(10)
     }
 
     fn cooldown(&self) -> u128 {
-        800
+        self.progression.cooldown(800)
     }
 
-    fn evoke(
-        &mut self,
-        location: Point2<f64>,
-        direction: Vector2<f64>,
-        ticker: u128,
-    ) -> Vec<Box<dyn Object>> {
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
         self.last_evoke = Some(ticker);
 
-        vec![Box::new(FireballObject::new(
-            location + direction.normalize(),
-            direction,
+        let count = self.progression.projectiles();
+        launch(
+            compose(
+                &[Pattern::Cone {
+                    count,
+                    angle: 0.2 * (count - 1) as f64,
+                }],
+                direction,
+            ),
+            location,
             ticker,
-        ))]
+            |location, direction, ticker| {
+                fireball(location, direction, ticker).with_pierce(self.progression.piercing())
+            },
+        )
+        .into()
     }
 
     fn get_spell(&self) -> Spell {
         Spell::Fireball
     }
 
-    fn on_cooldown(&self, ticker: u128) -> bool {
-        self.remaining_cooldown(ticker) > 0
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
     }
 
-    fn remaining_cooldown(&self, ticker: u128) -> u128 {
-        if let Some(last_evoke) = self.last_evoke {
-            return (last_evoke + self.cooldown()).saturating_sub(ticker);
-        }
-        0
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
     }
 }
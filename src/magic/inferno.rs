@@ -1,176 +1,89 @@
 use nalgebra::{Point2, Vector2};
 
 use crate::{
-    direction::{AsVector2, Direction},
-    magic::fireball::FireballObject,
-    Object,
+    ability::{Ability, Evocation},
+    magic::fireball::fireball,
 };
 
-use super::{Magic, Spell};
+use super::{
+    emission::{compose, launch, Pattern},
+    progression::Progression,
+    resource::Resource,
+    Spell,
+};
 
-pub struct InfernoMagic {
+pub struct InfernoAbility {
     last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
 }
 
-impl InfernoMagic {
+impl InfernoAbility {
     pub fn new() -> Self {
-        Self { last_evoke: None }
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Combo,
+        }
     }
 }
 
-impl Magic for InfernoMagic {
+impl Ability for InfernoAbility {
     fn cost(&self) -> u32 {
-        80
+        self.progression.cost(80)
     }
 
     fn cooldown(&self) -> u128 {
-        40_000
+        self.progression.cooldown(40_000)
     }
 
-    fn evoke(
-        &mut self,
-        location: Point2<f64>,
-        _direction: Vector2<f64>,
-        ticker: u128,
-    ) -> Vec<Box<dyn Object>> {
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
         self.last_evoke = Some(ticker);
 
-        vec![
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector() + Direction::Left.as_vector(),
-                Direction::Up.as_vector() + Direction::Left.as_vector() * 2.,
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector() + Direction::Left.as_vector(),
-                Direction::Up.as_vector() + Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector() + Direction::Left.as_vector(),
-                Direction::Up.as_vector() * 2. + Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector(),
-                Direction::Up.as_vector() * 2. + Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector(),
-                Direction::Up.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector(),
-                Direction::Up.as_vector() * 2. + Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector() + Direction::Right.as_vector(),
-                Direction::Up.as_vector() * 2. + Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector() + Direction::Right.as_vector(),
-                Direction::Up.as_vector() + Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Up.as_vector() + Direction::Right.as_vector(),
-                Direction::Up.as_vector() + Direction::Right.as_vector() * 2.,
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Right.as_vector(),
-                Direction::Right.as_vector() * 2. + Direction::Up.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Right.as_vector(),
-                Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Right.as_vector(),
-                Direction::Right.as_vector() * 2. + Direction::Down.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector() + Direction::Right.as_vector(),
-                Direction::Down.as_vector() + Direction::Right.as_vector() * 2.,
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector() + Direction::Right.as_vector(),
-                Direction::Down.as_vector() + Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector() + Direction::Right.as_vector(),
-                Direction::Down.as_vector() * 2. + Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector(),
-                Direction::Down.as_vector() * 2. + Direction::Right.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector(),
-                Direction::Down.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector(),
-                Direction::Down.as_vector() * 2. + Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector() + Direction::Left.as_vector(),
-                Direction::Down.as_vector() * 2. + Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector() + Direction::Left.as_vector(),
-                Direction::Down.as_vector() + Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Down.as_vector() + Direction::Left.as_vector(),
-                Direction::Down.as_vector() + Direction::Left.as_vector() * 2.,
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Left.as_vector(),
-                Direction::Left.as_vector() * 2. + Direction::Down.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Left.as_vector(),
-                Direction::Left.as_vector(),
-                ticker,
-            )),
-            Box::new(FireballObject::new(
-                location + Direction::Left.as_vector(),
-                Direction::Left.as_vector() * 2. + Direction::Up.as_vector(),
-                ticker,
-            )),
-        ]
+        let patterns = [
+            Pattern::Ring {
+                count: 20 + 4 * self.progression.projectiles(),
+                radius: 1.5,
+            },
+            Pattern::Spiral {
+                count: 8,
+                turns: 1.,
+                interval: 150,
+            },
+        ];
+
+        launch(
+            compose(&patterns, direction),
+            location,
+            ticker,
+            |location, direction, ticker| {
+                fireball(location, direction, ticker).with_pierce(self.progression.piercing())
+            },
+        )
+        .into()
     }
 
     fn get_spell(&self) -> Spell {
         Spell::Inferno
     }
 
-    fn on_cooldown(&self, ticker: u128) -> bool {
-        self.remaining_cooldown(ticker) > 0
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
     }
 
-    fn remaining_cooldown(&self, ticker: u128) -> u128 {
-        if let Some(last_evoke) = self.last_evoke {
-            return (last_evoke + self.cooldown()).saturating_sub(ticker);
-        }
-        0
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
     }
 }
@@ -0,0 +1,86 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    ability::{Ability, Evocation},
+    collision::Hitbox,
+    magic::{
+        emission::{compose, launch, Pattern},
+        progression::Progression,
+        projectile::Projectile,
+        resource::Resource,
+        Spell,
+    },
+};
+
+pub fn orb(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Projectile {
+    Projectile::new(Spell::Orb, 0.004, location, direction, ticker)
+        .with_damage(2)
+        .with_hitbox(Hitbox::circle(0.6))
+        .with_knockback(1.5)
+}
+
+#[derive(Debug)]
+pub struct OrbAbility {
+    last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
+}
+
+impl OrbAbility {
+    pub fn new() -> Self {
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Energy,
+        }
+    }
+}
+
+impl Ability for OrbAbility {
+    fn cost(&self) -> u32 {
+        self.progression.cost(25)
+    }
+
+    fn cooldown(&self) -> u128 {
+        self.progression.cooldown(2_000)
+    }
+
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
+        self.last_evoke = Some(ticker);
+
+        let count = self.progression.projectiles();
+        launch(
+            compose(&[Pattern::Burst { count }], direction),
+            location,
+            ticker,
+            |location, direction, ticker| {
+                orb(location, direction, ticker).with_pierce(self.progression.piercing())
+            },
+        )
+        .into()
+    }
+
+    fn get_spell(&self) -> Spell {
+        Spell::Orb
+    }
+
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
+    }
+
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
+    }
+}
@@ -0,0 +1,141 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Upgrade {
+    ExtraProjectile,
+    LowerCost,
+    FasterCooldown,
+    Piercing,
+}
+
+pub const ALL_UPGRADES: [Upgrade; 4] = [
+    Upgrade::ExtraProjectile,
+    Upgrade::LowerCost,
+    Upgrade::FasterCooldown,
+    Upgrade::Piercing,
+];
+
+impl Upgrade {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Upgrade::ExtraProjectile => "+1 projectile",
+            Upgrade::LowerCost => "-15% energy cost",
+            Upgrade::FasterCooldown => "-15% cooldown",
+            Upgrade::Piercing => "projectiles pierce",
+        }
+    }
+}
+
+pub const POINTS_PER_EVOKE: u32 = 1;
+pub const POINTS_PER_KILL: u32 = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Progression {
+    pub level: u32,
+    pub points: u32,
+    pub unspent: u32,
+    extra_projectiles: u32,
+    cost_reductions: u32,
+    cooldown_reductions: u32,
+    piercing: bool,
+}
+
+impl Progression {
+    pub fn new() -> Self {
+        Self {
+            level: 1,
+            points: 0,
+            unspent: 0,
+            extra_projectiles: 0,
+            cost_reductions: 0,
+            cooldown_reductions: 0,
+            piercing: false,
+        }
+    }
+
+    pub fn next_level_points(&self) -> u32 {
+        10 * self.level
+    }
+
+    pub fn gain(&mut self, points: u32) {
+        self.points += points;
+        while self.points >= self.next_level_points() {
+            self.points -= self.next_level_points();
+            self.level += 1;
+            self.unspent += 1;
+        }
+    }
+
+    pub fn can_apply(&self, upgrade: Upgrade) -> bool {
+        match upgrade {
+            Upgrade::Piercing => !self.piercing,
+            Upgrade::LowerCost => self.cost_reductions < 5,
+            Upgrade::FasterCooldown => self.cooldown_reductions < 5,
+            Upgrade::ExtraProjectile => self.extra_projectiles < 4,
+        }
+    }
+
+    pub fn apply(&mut self, upgrade: Upgrade) {
+        self.unspent = self.unspent.saturating_sub(1);
+        match upgrade {
+            Upgrade::ExtraProjectile => self.extra_projectiles += 1,
+            Upgrade::LowerCost => self.cost_reductions += 1,
+            Upgrade::FasterCooldown => self.cooldown_reductions += 1,
+            Upgrade::Piercing => self.piercing = true,
+        }
+    }
+
+    pub fn cost(&self, base: u32) -> u32 {
+        ((base as f64) * 0.85_f64.powi(self.cost_reductions as i32))
+            .round()
+            .max(1.) as u32
+    }
+
+    pub fn cooldown(&self, base: u128) -> u128 {
+        ((base as f64) * 0.85_f64.powi(self.cooldown_reductions as i32)).round() as u128
+    }
+
+    pub fn projectiles(&self) -> u32 {
+        1 + self.extra_projectiles
+    }
+
+    pub fn piercing(&self) -> bool {
+        self.piercing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_levels_up_with_carry_over() {
+        let mut progression = Progression::new();
+
+        progression.gain(25);
+
+        assert_eq!(progression.level, 2);
+        assert_eq!(progression.points, 15);
+        assert_eq!(progression.unspent, 1);
+
+        progression.gain(5);
+
+        assert_eq!(progression.level, 3);
+        assert_eq!(progression.points, 0);
+        assert_eq!(progression.unspent, 2);
+    }
+
+    #[test]
+    fn upgrades_modify_numbers() {
+        let mut progression = Progression::new();
+        progression.gain(10);
+
+        progression.apply(Upgrade::LowerCost);
+        progression.apply(Upgrade::FasterCooldown);
+        progression.apply(Upgrade::ExtraProjectile);
+
+        assert_eq!(progression.unspent, 0);
+        assert_eq!(progression.cost(80), 68);
+        assert_eq!(progression.cooldown(800), 680);
+        assert_eq!(progression.projectiles(), 2);
+        assert_eq!(progression.cost(1), 1);
+    }
+}
@@ -0,0 +1,138 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{collision::Hitbox, magic::Spell, Object};
+
+// A shot flying in a straight line, what sets spells apart is set up with
+// the `with_` methods
+#[derive(Clone, Debug)]
+pub struct Projectile {
+    spell: Spell,
+    location: Point2<f64>,
+    vector: Vector2<f64>,
+    hitbox: Hitbox,
+    pierce: bool,
+    knockback: f64,
+    damage: u32,
+    hostile: bool,
+    // Monsters already hit, piercing shots pass through them
+    struck: Vec<u64>,
+    until: Option<u128>,
+    due: u128,
+    last_tick: u128,
+}
+
+impl Projectile {
+    pub fn new(
+        spell: Spell,
+        speed: f64,
+        location: Point2<f64>,
+        direction: Vector2<f64>,
+        ticker: u128,
+    ) -> Self {
+        Self {
+            spell,
+            location,
+            vector: direction.normalize() * speed,
+            hitbox: Hitbox::circle(0.4),
+            pierce: false,
+            knockback: 0.,
+            damage: 1,
+            hostile: false,
+            struck: Vec::new(),
+            until: None,
+            due: ticker,
+            last_tick: ticker,
+        }
+    }
+
+    pub fn with_pierce(mut self, pierce: bool) -> Self {
+        self.pierce = pierce;
+        self
+    }
+
+    pub fn with_hitbox(mut self, hitbox: Hitbox) -> Self {
+        self.hitbox = hitbox;
+        self
+    }
+
+    pub fn with_knockback(mut self, knockback: f64) -> Self {
+        self.knockback = knockback;
+        self
+    }
+
+    pub fn with_damage(mut self, damage: u32) -> Self {
+        self.damage = damage;
+        self
+    }
+
+    // Aimed at the player instead of monsters
+    pub fn with_hostile(mut self, hostile: bool) -> Self {
+        self.hostile = hostile;
+        self
+    }
+
+    // Fades after `duration` instead of flying until it hits something
+    pub fn with_lifetime(mut self, duration: u128) -> Self {
+        self.until = Some(self.last_tick + duration);
+        self
+    }
+}
+
+impl Object for Projectile {
+    fn location(&self) -> Point2<f64> {
+        self.location
+    }
+
+    fn vector(&self) -> Vector2<f64> {
+        self.vector
+    }
+
+    fn set_location(&mut self, location: Point2<f64>, ticker: u128) {
+        self.location = location;
+        self.last_tick = ticker;
+    }
+
+    fn get_spell(&self) -> Spell {
+        self.spell
+    }
+
+    fn next_location(&self, ticker: u128) -> Point2<f64> {
+        self.location + self.vector * ticker.saturating_sub(self.last_tick) as f64
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        self.hitbox
+    }
+
+    fn pierces(&self) -> bool {
+        self.pierce
+    }
+
+    fn expired(&self, ticker: u128) -> bool {
+        self.until.is_some_and(|until| ticker >= until)
+    }
+
+    fn knockback(&self) -> f64 {
+        self.knockback
+    }
+
+    fn due(&self) -> u128 {
+        self.due
+    }
+
+    fn hostile(&self) -> bool {
+        self.hostile
+    }
+
+    fn damage(&self) -> u32 {
+        self.damage
+    }
+
+    fn strike(&mut self, id: u64) -> bool {
+        if self.struck.contains(&id) {
+            return false;
+        }
+        self.struck.push(id);
+        true
+    }
+}
@@ -0,0 +1,64 @@
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resource {
+    Energy,
+    Charges(Charges),
+    Blood,
+    Combo,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Charges {
+    pub max: u32,
+    pub recharge: u128,
+    ready_at: Vec<u128>,
+}
+
+impl Charges {
+    pub fn new(max: u32, recharge: u128) -> Self {
+        Self {
+            max,
+            recharge,
+            ready_at: Vec::new(),
+        }
+    }
+
+    pub fn available(&self, ticker: u128) -> u32 {
+        let recharging = self
+            .ready_at
+            .iter()
+            .filter(|ready| **ready > ticker)
+            .count() as u32;
+        self.max.saturating_sub(recharging)
+    }
+
+    // Each spent charge recharges on its own timer
+    pub fn spend(&mut self, ticker: u128) {
+        self.ready_at.retain(|ready| *ready > ticker);
+        self.ready_at.push(ticker + self.recharge);
+    }
+}
+
+pub const MAX_COMBO: u32 = 100;
+pub const COMBO_PER_KILL: u32 = 20;
+pub const COMBO_DECAY_DELAY: u128 = 3_000;
+pub const COMBO_DECAY_RATE: u128 = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_recharge_independently() {
+        let mut charges = Charges::new(3, 1_000);
+
+        charges.spend(0);
+        charges.spend(500);
+
+        assert_eq!(charges.available(600), 1);
+        assert_eq!(charges.available(1_000), 2);
+        assert_eq!(charges.available(1_500), 3);
+
+        charges.spend(1_500);
+        assert_eq!(charges.available(1_600), 2);
+    }
+}
@@ -0,0 +1,88 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    ability::{Ability, Evocation},
+    magic::{
+        emission::{compose, launch, Pattern},
+        progression::Progression,
+        projectile::Projectile,
+        resource::Resource,
+        Spell,
+    },
+};
+
+pub fn spark(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Projectile {
+    Projectile::new(Spell::Spark, 0.02, location, direction, ticker)
+}
+
+#[derive(Debug)]
+pub struct SparkAbility {
+    last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
+}
+
+impl SparkAbility {
+    pub fn new() -> Self {
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Blood,
+        }
+    }
+}
+
+impl Ability for SparkAbility {
+    fn cost(&self) -> u32 {
+        self.progression.cost(5)
+    }
+
+    fn cooldown(&self) -> u128 {
+        self.progression.cooldown(600)
+    }
+
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
+        self.last_evoke = Some(ticker);
+
+        let count = self.progression.projectiles();
+        launch(
+            compose(
+                &[Pattern::Cone {
+                    count,
+                    angle: 0.2 * (count - 1) as f64,
+                }],
+                direction,
+            ),
+            location,
+            ticker,
+            |location, direction, ticker| {
+                spark(location, direction, ticker).with_pierce(self.progression.piercing())
+            },
+        )
+        .into()
+    }
+
+    fn get_spell(&self) -> Spell {
+        Spell::Spark
+    }
+
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
+    }
+
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
+    }
+}
@@ -1,97 +1,82 @@
 use nalgebra::{Point2, Vector2};
 
 use crate::{
-    magic::{Magic, Spell},
-    Object,
+    ability::{Ability, Evocation},
+    magic::{
+        emission::{compose, launch, Pattern},
+        progression::Progression,
+        projectile::Projectile,
+        resource::Resource,
+        Spell,
+    },
 };
 
-#[derive(Debug)]
-pub struct SphereObject {
-    pub location: Point2<f64>,
-    pub vector: Vector2<f64>,
-    last_tick: u128,
-}
-
-impl SphereObject {
-    pub fn new(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Self {
-        Self {
-            location,
-            vector: direction.normalize() * 0.005,
-            last_tick: ticker,
-        }
-    }
-}
-
-impl Object for SphereObject {
-    fn location(&self) -> Point2<f64> {
-        self.location
-    }
-
-    fn vector(&self) -> Vector2<f64> {
-        self.vector
-    }
-
-    fn set_location(&mut self, location: Point2<f64>, ticker: u128) {
-        self.location = location;
-        self.last_tick = ticker;
-    }
-
-    fn get_spell(&self) -> Spell {
-        Spell::Sphere
-    }
-
-    fn next_location(&self, ticker: u128) -> Point2<f64> {
-        self.location + self.vector * ticker.saturating_sub(self.last_tick) as f64
-    }
+pub fn sphere(location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Projectile {
+    Projectile::new(Spell::Sphere, 0.005, location, direction, ticker)
 }
 
 #[derive(Debug)]
-pub struct SphereMagic {
+pub struct SphereAbility {
     last_evoke: Option<u128>,
+    progression: Progression,
+    resource: Resource,
 }
 
-impl SphereMagic {
+impl SphereAbility {
     pub fn new() -> Self {
-        Self { last_evoke: None }
+        Self {
+            last_evoke: None,
+            progression: Progression::new(),
+            resource: Resource::Energy,
+        }
     }
 }
 
-impl Magic for SphereMagic {
+impl Ability for SphereAbility {
     fn cost(&self) -> u32 {
-        5
+        self.progression.cost(5)
     }
 
     fn cooldown(&self) -> u128 {
-        400
+        self.progression.cooldown(400)
     }
 
-    fn evoke(
-        &mut self,
-        location: Point2<f64>,
-        direction: Vector2<f64>,
-        ticker: u128,
-    ) -> Vec<Box<dyn Object>> {
+    fn evoke(&mut self, location: Point2<f64>, direction: Vector2<f64>, ticker: u128) -> Evocation {
         self.last_evoke = Some(ticker);
 
-        vec![Box::new(SphereObject::new(
-            location + direction.normalize(),
-            direction,
+        let count = self.progression.projectiles();
+        launch(
+            compose(&[Pattern::Wall { count, spacing: 1. }], direction),
+            location,
             ticker,
-        ))]
+            |location, direction, ticker| {
+                sphere(location, direction, ticker).with_pierce(self.progression.piercing())
+            },
+        )
+        .into()
     }
 
     fn get_spell(&self) -> Spell {
         Spell::Sphere
     }
 
-    fn on_cooldown(&self, ticker: u128) -> bool {
-        self.remaining_cooldown(ticker) > 0
+    fn last_evoke(&self) -> Option<u128> {
+        self.last_evoke
     }
 
-    fn remaining_cooldown(&self, ticker: u128) -> u128 {
-        if let Some(last_evoke) = self.last_evoke {
-            return (last_evoke + self.cooldown()).saturating_sub(ticker);
-        }
-        0
+    fn progression(&self) -> &Progression {
+        &self.progression
+    }
+
+    fn progression_mut(&mut self) -> &mut Progression {
+        &mut self.progression
+    }
+
+    fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    fn resource_mut(&mut self) -> &mut Resource {
+        &mut self.resource
     }
 }
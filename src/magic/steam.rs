@@ -0,0 +1,78 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::{
+    collision::Hitbox,
+    magic::{element::Element, Spell},
+    Object,
+};
+
+const STEAM_DURATION: u128 = 2_500;
+
+#[derive(Debug)]
+pub struct SteamObject {
+    pub location: Point2<f64>,
+    pub vector: Vector2<f64>,
+    last_tick: u128,
+    until: u128,
+    struck: Vec<u64>,
+}
+
+impl SteamObject {
+    pub fn new(location: Point2<f64>, vector: Vector2<f64>, ticker: u128) -> Self {
+        Self {
+            location,
+            vector: vector * 0.2,
+            last_tick: ticker,
+            until: ticker + STEAM_DURATION,
+            struck: Vec::new(),
+        }
+    }
+}
+
+impl Object for SteamObject {
+    fn location(&self) -> Point2<f64> {
+        self.location
+    }
+
+    fn vector(&self) -> Vector2<f64> {
+        self.vector
+    }
+
+    fn set_location(&mut self, location: Point2<f64>, ticker: u128) {
+        self.location = location;
+        self.last_tick = ticker;
+    }
+
+    fn get_spell(&self) -> Spell {
+        Spell::Steam
+    }
+
+    fn next_location(&self, ticker: u128) -> Point2<f64> {
+        self.location + self.vector * ticker.saturating_sub(self.last_tick) as f64
+    }
+
+    fn element(&self) -> Option<Element> {
+        // Scalding hot, shatters frozen monsters like fire does
+        Some(Element::Fire)
+    }
+
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::circle(0.8)
+    }
+
+    fn pierces(&self) -> bool {
+        true
+    }
+
+    fn expired(&self, ticker: u128) -> bool {
+        ticker >= self.until
+    }
+
+    fn strike(&mut self, id: u64) -> bool {
+        if self.struck.contains(&id) {
+            return false;
+        }
+        self.struck.push(id);
+        true
+    }
+}
@@ -1,43 +1,98 @@
-use command::{AsCommand, Command};
-use console::{ConsoleDisplay, ConsoleUnit, InputTracker};
+use command::Command;
+use console::{
+    AsSymbol, ColorDepth, ConsoleDisplay, ConsoleUnit, DevConsole, Glyphs, InputTracker,
+    KeyReleases, Keymap, Palette, Theme, SIDE_PANEL_WIDTH,
+};
 use crossterm::{
     cursor,
-    event::{self, poll, read, Event, KeyCode, KeyEvent, KeyEventKind},
+    event::{self, poll, read, Event, KeyboardEnhancementFlags},
     execute,
+    style::Color,
     terminal::{self, size, SetSize},
 };
 
+use ability::Evocation;
+use dev_command::{DevCommand, Gift};
 use direction::AsVector2;
 use display::Display;
-use entity::monster::Monster;
+use entity::ally::{Ally, WRAITH_DAMAGE, WRAITH_KNOCKBACK, WRAITH_SIGHT};
+use entity::collision::{blocking, overlaps, sweep, Hitbox, MAX_OBJECT_REACH, MAX_UNIT_REACH};
+use entity::equipment::{Gear, GEAR};
+use entity::experience::{Reward, STAT_REWARDS};
+use entity::item::{Item, Pickup, CONSUMABLES, PICKUP_HITBOX};
+use entity::monster::{Archetype, Monster};
 use entity::object::Object;
-use entity::player::Player;
+use entity::physics::blast_distance;
+use entity::player::{
+    Player, CONTACT_KNOCKBACK, MONSTER_CONTACT_DAMAGE, SWING_DAMAGE, SWING_KNOCKBACK, SWING_RANGE,
+};
+use entity::spatial::SpatialGrid;
 use log::{debug, info, LevelFilter};
+use magic::element::{combine, impact, Aura, Combination, Element, Impact, FROZEN_DURATION};
+use magic::progression::Upgrade;
+use magic::steam::SteamObject;
+use magic::Spell;
 use nalgebra::{convert, vector, Point2, Scale2, Vector2};
-use render_action::RenderAction;
+use rand::{random, rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use render_action::{Effect, RenderAction};
+use replay::{replay_path, Frame, Playback, Recorder, Replay, Session, Settings};
 use simplelog::{format_description, ConfigBuilder, WriteLogger};
 
 use std::{
+    collections::HashMap,
     fs::File,
-    io,
+    io, mem,
     time::{Duration, Instant},
 };
 
+mod ability;
+mod class;
 mod command;
 mod console;
+mod dev_command;
 mod direction;
 mod display;
 mod entity;
+mod loadout;
 mod magic;
+mod menu;
 mod render_action;
+mod replay;
 use crate::entity::*;
 
 struct State {
     score: i32,
+    floor: u32,
     ticker: u128,
     monsters: Vec<Monster>,
     player: Player,
     objects: Vec<Box<dyn Object>>,
+    // Evoked but not yet due
+    queued: Vec<Box<dyn Object>>,
+    allies: Vec<Ally>,
+    items: Vec<Pickup>,
+    // Pickups by their slot in `items`
+    item_grid: SpatialGrid<usize>,
+    rng: StdRng,
+}
+
+// Game time runs at an adjustable rate against the unpaused wall clock
+struct GameSpeed {
+    factor: f64,
+    since: u128,
+    ticker: u128,
+}
+
+impl GameSpeed {
+    fn ticker(&self, unpaused: u128) -> u128 {
+        self.ticker + (unpaused.saturating_sub(self.since) as f64 * self.factor) as u128
+    }
+
+    fn set(&mut self, factor: f64, unpaused: u128, ticker: u128) {
+        self.factor = factor;
+        self.since = unpaused;
+        self.ticker = ticker;
+    }
 }
 
 trait AsCoord {
@@ -70,6 +125,37 @@ fn main() -> io::Result<()> {
 
     info!("Running");
 
+    let replay = match std::env::args().skip_while(|arg| arg != "--replay").nth(1) {
+        Some(path) => Some(Replay::load(&path)?),
+        None => None,
+    };
+
+    let glyphs = match std::env::args().skip_while(|arg| arg != "--glyphs").nth(1) {
+        Some(name) => name
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+        None => Glyphs::detect(),
+    };
+    info!("Glyphs: {:?}", glyphs);
+    glyphs.install();
+
+    let palette = Palette {
+        theme: match std::env::args().skip_while(|arg| arg != "--palette").nth(1) {
+            Some(name) => name
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+            None => Theme::detect(),
+        },
+        depth: match std::env::args().skip_while(|arg| arg != "--colors").nth(1) {
+            Some(name) => name
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+            None => ColorDepth::detect(),
+        },
+    };
+    info!("Palette: {:?}", palette);
+    palette.install();
+
     let (cols, rows) = size()?;
 
     // execute!(
@@ -86,7 +172,27 @@ fn main() -> io::Result<()> {
         event::EnableMouseCapture,
         cursor::Hide,
     )?;
-    let score = game(&mut stdout);
+
+    // Kitty keyboard protocol, the only way to learn about key releases
+    let key_releases = if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+        execute!(
+            stdout,
+            event::PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+            )
+        )?;
+        KeyReleases::Reported
+    } else {
+        KeyReleases::Synthesized
+    };
+    info!("Key releases: {:?}", key_releases);
+
+    let score = game(&mut stdout, key_releases, replay);
+    if key_releases == KeyReleases::Reported {
+        execute!(stdout, event::PopKeyboardEnhancementFlags)?;
+    }
     execute!(
         stdout,
         terminal::LeaveAlternateScreen,
@@ -102,19 +208,27 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
-fn game(stdout: &mut io::Stdout) -> io::Result<i32> {
+fn game(
+    stdout: &mut io::Stdout,
+    key_releases: KeyReleases,
+    replay: Option<(Settings, Replay)>,
+) -> io::Result<i32> {
     let (t_cols, t_rows) = size()?;
     debug!("size: {:?}", size()?);
 
     let scale = Scale2::new(2, 1);
 
-    let game_cols = t_cols - 2;
-    let game_rows = t_rows - 2;
+    let game_cols = t_cols.saturating_sub(2 + SIDE_PANEL_WIDTH);
+    let game_rows = t_rows.saturating_sub(2);
 
-    let game_bounds = Vector2::<u16>::new(
-        u16::min((game_cols - 2) / 2, 29),
-        u16::min(game_rows - 1, 29),
+    let mut game_bounds = Vector2::<u16>::new(
+        (game_cols.saturating_sub(2) / 2).clamp(MIN_GAME_BOUNDS.x, 29),
+        game_rows.saturating_sub(1).clamp(MIN_GAME_BOUNDS.y, 29),
     );
+    // Replays play out in the arena they were recorded in
+    if let Some((settings, _)) = &replay {
+        game_bounds = settings.game_bounds;
+    }
     debug!("game_bounds: {:?}", game_bounds);
 
     let display_bounds = scale * (game_bounds + vector![1, 1]) + vector![1, 1];
@@ -126,237 +240,617 @@ fn game(stdout: &mut io::Stdout) -> io::Result<i32> {
     let dimensions = Vector2::new(t_cols, t_rows);
     debug!("dimensions: {:?}", dimensions);
 
-    let display_top_left = Point2::new(
-        (t_cols - display_bounds.x) / 2,
-        (t_rows - display_bounds.y) / 3,
-    );
+    let mut display = ConsoleDisplay::new(dimensions, display_bounds, scale, stdout);
+
+    let mut keymap = Keymap::load();
+    let mut dev_console = DevConsole::new();
 
-    let mut display = ConsoleDisplay::new(display_top_left, display_bounds, scale, stdout);
+    let mut terminal_size = (t_cols, t_rows);
+    if !display.resize(t_cols, t_rows)? {
+        match wait_for_room(&mut display, &keymap)? {
+            Some(size) => terminal_size = size,
+            None => return Ok(0),
+        }
+    }
 
+    // Only now the frame has settled where it will be drawn
     let mut input_tracker = InputTracker::new_mouse(
-        Point2::new(t_cols / 2, t_rows / 2),
+        Point2::new(terminal_size.0 / 2, terminal_size.1 / 2),
         convert(display.game_area_offset()),
         convert(scale),
+        key_releases,
     );
 
+    let (settings, mut session) = match replay {
+        Some((settings, replay)) => (settings, Session::Replay(replay)),
+        None => {
+            let (class, spells) = class::choose_class(&mut display, &keymap)?;
+            let settings = Settings {
+                seed: random(),
+                game_bounds,
+                class,
+                spells,
+            };
+            let path = replay_path();
+            info!("Recording replay to {}", path);
+            let recorder = Recorder::create(&path, &settings)?;
+            (settings, Session::Record(recorder))
+        }
+    };
+
     let timer = Instant::now();
 
     let mut state = State {
         ticker: 0,
         score: 0,
-        player: Player::new(Point2::new(bounds.x / 2., bounds.y / 2.), 0),
-        monsters: vec![
-            Monster::new_simple(Point2::new(bounds.x / 4., bounds.y / 4.), 0),
-            Monster::new(
-                Point2::new(bounds.x * 3. / 4., bounds.y / 4.),
-                0,
-                Some(40),
-                Some(3.),
-            ),
-            Monster::new(
-                Point2::new(bounds.x * 3. / 4., bounds.y * 3. / 4.),
-                0,
-                Some(150),
-                None,
-            ),
-            Monster::new(
-                Point2::new(bounds.x / 4., bounds.y * 3. / 4.),
-                0,
-                Some(200),
-                None,
-            ),
-        ],
+        floor: 1,
+        player: Player::new(
+            Point2::new(bounds.x / 2., bounds.y / 2.),
+            0,
+            settings.class,
+            &settings.spells,
+        ),
+        monsters: Vec::new(),
         objects: Vec::new(),
+        queued: Vec::new(),
+        allies: Vec::new(),
+        items: Vec::new(),
+        item_grid: SpatialGrid::new(GRID_CELL_SIZE),
+        rng: StdRng::seed_from_u64(settings.seed),
     };
+    populate_floor(&mut state, bounds);
 
     display.draw_initial(&state)?;
 
     let mut last_spawn_tick = 0;
 
     let mut exit = false;
+    let mut cause_of_death: Option<String> = None;
+    let mut died: Option<u128> = None;
     let mut pause: Option<u128> = None;
     let mut pause_ticker = 0;
+    let mut game_speed = GameSpeed {
+        factor: 1.,
+        since: 0,
+        ticker: 0,
+    };
+    let mut pending_dev: Vec<DevCommand> = Vec::new();
+    let mut offered_level = state.player.experience.level;
 
     loop {
-        if poll(Duration::from_millis(20))? {
-            let event = read();
-            match event {
-                Ok(Event::Key(KeyEvent {
-                    code: KeyCode::Esc, ..
-                })) => {
-                    exit = true;
-                }
-                Ok(Event::Key(KeyEvent {
-                    code: KeyCode::Home,
-                    kind: KeyEventKind::Release,
-                    ..
-                })) => {
-                    if pause.is_none() {
-                        pause = Some(timer.elapsed().as_millis());
-                    } else {
-                        pause_ticker += timer.elapsed().as_millis() - pause.unwrap();
-                        pause = None;
-                    }
+        // Polled rather than read from events, menus and the replay controls
+        // consume those
+        if size()? != terminal_size {
+            let resize_start = timer.elapsed().as_millis();
+            terminal_size = size()?;
+            if !display.resize(terminal_size.0, terminal_size.1)? {
+                match wait_for_room(&mut display, &keymap)? {
+                    Some(size) => terminal_size = size,
+                    None => break,
                 }
-                Ok(Event::Key(KeyEvent {
-                    code: KeyCode::Insert,
-                    kind: KeyEventKind::Release,
-                    ..
-                })) => {
-                    debug!("Player.location {:?}", state.player.location());
-                    debug!("Player.coord    {:?}", state.player.location().as_coord());
-                }
-                Ok(ok_event) => {
-                    if pause.is_none() {
-                        input_tracker.register_input_event(ok_event);
+            }
+            if pause.is_none() {
+                pause_ticker += timer.elapsed().as_millis() - resize_start;
+            }
+            if let Session::Replay(replay) = &mut session {
+                replay.resume();
+            }
+
+            input_tracker.set_game_area_offset(convert(display.game_area_offset()));
+            input_tracker.reset();
+            display.draw_initial(&state)?;
+        }
+
+        let frame = match &mut session {
+            Session::Record(recorder) => {
+                if poll(Duration::from_millis(20))? {
+                    let event = read()?;
+                    match keymap.system_command(&event) {
+                        Some(Command::Quit) => {
+                            exit = true;
+                        }
+                        Some(Command::Pause) => {
+                            if pause.is_none() {
+                                pause = Some(timer.elapsed().as_millis());
+                            } else {
+                                pause_ticker += timer.elapsed().as_millis() - pause.unwrap();
+                                pause = None;
+                            }
+                        }
+                        Some(Command::DebugInfo) => {
+                            debug!("Player.location {:?}", state.player.location());
+                            debug!("Player.coord    {:?}", state.player.location().as_coord());
+                        }
+                        Some(Command::Rebind) => {
+                            let menu_start = timer.elapsed().as_millis();
+                            keymap.rebind_screen(&mut display)?;
+                            if pause.is_none() {
+                                pause_ticker += timer.elapsed().as_millis() - menu_start;
+                            }
+
+                            input_tracker.reset();
+                            display.draw_initial(&state)?;
+                        }
+                        Some(Command::DevConsole) => {
+                            let menu_start = timer.elapsed().as_millis();
+                            let mut message = String::from("Tab completes, Esc closes");
+                            let mut entered = Vec::new();
+                            while let Some(line) =
+                                dev_console.read_line(&mut display, &keymap, &message)?
+                            {
+                                message = match line.parse::<DevCommand>() {
+                                    Ok(command) => {
+                                        entered.push(command);
+                                        format!("ok: {}", command)
+                                    }
+                                    Err(err) => err,
+                                };
+                            }
+                            if pause.is_none() {
+                                pause_ticker += timer.elapsed().as_millis() - menu_start;
+                            }
+
+                            for command in &entered {
+                                if let DevCommand::Speed(factor) = command {
+                                    game_speed.set(
+                                        *factor,
+                                        timer.elapsed().as_millis() - pause_ticker,
+                                        state.ticker,
+                                    );
+                                }
+                            }
+                            pending_dev.extend(entered);
+
+                            input_tracker.reset();
+                            display.draw_initial(&state)?;
+                        }
+                        _ if pause.is_none() => {
+                            input_tracker.register_input_event(event);
+                        }
+                        _ => {}
                     }
                 }
-                _ => {}
+                if exit {
+                    break;
+                }
+
+                if pause.is_some() {
+                    continue;
+                }
+
+                let (input_state, mouse_coord, modifiers) = input_tracker.calculate_state();
+                let frame = Frame {
+                    ticker: game_speed.ticker(timer.elapsed().as_millis() - pause_ticker),
+                    dev: std::mem::take(&mut pending_dev),
+                    commands: input_state
+                        .iter()
+                        .filter_map(|key_state| keymap.command(key_state, modifiers))
+                        .collect(),
+                    mouse: *mouse_coord,
+                };
+                recorder.frame(&frame)?;
+                frame
             }
-        }
-        if exit {
-            break;
-        }
+            Session::Replay(replay) => match replay.next_frame(&keymap)? {
+                Playback::Play(frame) => frame,
+                Playback::Wait => continue,
+                Playback::Stop => {
+                    display.draw_summary(
+                        "Replay finished",
+                        &[
+                            format!("Floor reached: {}", state.floor),
+                            format!("Score: {}", state.score),
+                        ],
+                    )?;
+                    menu::wait_for_key()?;
+                    break;
+                }
+            },
+        };
 
-        if pause.is_some() {
-            continue;
-        }
+        state.ticker = frame.ticker;
 
-        state.ticker = timer.elapsed().as_millis() - pause_ticker;
+        for command in &frame.dev {
+            apply_dev_command(&mut state, &mut display, *command, bounds);
+        }
 
         // OBJECTS
 
-        let object_len = state.objects.len();
-
-        for object_ix in (0..object_len).rev() {
-            let object = &state.objects[object_ix];
+        let (due, queued): (Vec<_>, Vec<_>) = mem::take(&mut state.queued)
+            .into_iter()
+            .partition(|object| object.due() <= state.ticker);
+        state.queued = queued;
+        spawn_objects(&mut state, &mut display, due, bounds);
+
+        // Rebuilt every tick and kept in sync as monsters die or move
+        let mut monster_grid = SpatialGrid::build(
+            GRID_CELL_SIZE,
+            state
+                .monsters
+                .iter()
+                .map(|m| ((m.id, m.hitbox()), m.location())),
+        );
+
+        let mut monster_index: HashMap<u64, usize> = state
+            .monsters
+            .iter()
+            .enumerate()
+            .map(|(ix, m)| (m.id, ix))
+            .collect();
+
+        // Objects keep their slot through the tick, the grid is keyed by slot
+        let mut objects: Vec<Option<Box<dyn Object>>> = state.objects.drain(..).map(Some).collect();
+        let mut object_grid = SpatialGrid::build(
+            GRID_CELL_SIZE,
+            objects
+                .iter()
+                .enumerate()
+                .filter_map(|(ix, object)| Some((ix, object.as_ref()?.location()))),
+        );
+
+        for object_ix in 0..objects.len() {
+            let Some(mut object) = objects[object_ix].take() else {
+                continue;
+            };
             let old_pos = object.location();
             let next_pos = object.next_location(state.ticker);
 
-            let old_coord = old_pos.as_coord();
-            let next_coord = next_pos.as_coord();
+            if object.expired(state.ticker)
+                || next_pos.x <= 0.
+                || next_pos.x >= bounds.x
+                || next_pos.y <= 0.
+                || next_pos.y >= bounds.y
+            {
+                object_grid.remove(object_ix, old_pos);
+                display.enqueue_action(RenderAction::Remove {
+                    coord: old_pos,
+                    symbol: object.symbol(),
+                });
+                continue;
+            }
 
-            if old_coord != next_coord {
-                let mut object = state.objects.remove(object_ix);
-                if next_pos.x > 0.
-                    && next_pos.x < bounds.x
-                    && next_pos.y > 0.
-                    && next_pos.y < bounds.y
-                {
-                    let mut hit = false;
-
-                    for monster_ix in 0..state.monsters.len() {
-                        if (state.monsters[monster_ix].location() - next_pos).magnitude() < 1. {
-                            state.score += 1;
-
-                            let monster = state.monsters.remove(monster_ix);
-                            display.enqueue_action(RenderAction::Remove {
-                                coord: monster.location(),
-                                symbol: monster.symbol(),
-                            });
-                            display.enqueue_action(RenderAction::Remove {
-                                coord: object.location(),
-                                symbol: object.symbol(),
-                            });
-                            hit = true;
-                            break;
-                        }
+            if object.hostile()
+                && sweep(
+                    old_pos,
+                    next_pos,
+                    object.hitbox(),
+                    state.player.location(),
+                    state.player.hitbox(),
+                )
+                .is_some()
+            {
+                if state.player.take_damage(object.damage(), state.ticker) {
+                    display.enqueue_action(RenderAction::Effect {
+                        effect: Effect::HurtFlash,
+                        color: Color::Red,
+                        location: state.player.location(),
+                    });
+                    if state.player.is_dead() {
+                        cause_of_death
+                            .get_or_insert_with(|| format!("Killed by {}", object.symbol()));
                     }
+                }
+                if !object.pierces() {
+                    object_grid.remove(object_ix, old_pos);
+                    display.enqueue_action(RenderAction::Remove {
+                        coord: old_pos,
+                        symbol: object.symbol(),
+                    });
+                    continue;
+                }
+            }
 
-                    if !hit {
-                        object.set_location(next_pos, state.ticker);
+            // Objects yet to move this tick are swept against relative to their
+            // own motion, those coming from further away catch this one on their turn
+            let reaction = object.element().and_then(|element| {
+                object_grid
+                    .along(
+                        old_pos,
+                        next_pos,
+                        object.hitbox().reach() + MAX_OBJECT_REACH,
+                    )
+                    .into_iter()
+                    .filter_map(|(ix, location)| {
+                        let other = objects[ix].as_deref()?;
+                        let combination = combine(element, other.element()?)?;
+                        let motion = if ix > object_ix {
+                            other.next_location(state.ticker) - location
+                        } else {
+                            Vector2::zeros()
+                        };
+                        let t = sweep(
+                            old_pos,
+                            next_pos - motion,
+                            object.hitbox(),
+                            location,
+                            other.hitbox(),
+                        )?;
+                        Some((t, combination, ix))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+            });
 
-                        display.enqueue_action(RenderAction::Move {
-                            symbol: object.symbol(),
-                            color: object.color(),
-                            old: old_pos,
-                            new: next_pos,
+            if let Some((t, combination, other_ix)) = reaction {
+                let contact = old_pos + (next_pos - old_pos) * t;
+                let other = objects[other_ix].take().unwrap();
+                object_grid.remove(object_ix, old_pos);
+                object_grid.remove(other_ix, other.location());
+                display.enqueue_action(RenderAction::Remove {
+                    coord: old_pos,
+                    symbol: object.symbol(),
+                });
+                display.enqueue_action(RenderAction::Remove {
+                    coord: other.location(),
+                    symbol: other.symbol(),
+                });
+                display.enqueue_action(RenderAction::Effect {
+                    effect: Effect::Explosion,
+                    color: object.color(),
+                    location: contact,
+                });
+
+                blast(
+                    &mut state,
+                    &monster_grid,
+                    &monster_index,
+                    contact,
+                    BLAST_RADIUS,
+                    COMBINATION_BLAST,
+                );
+
+                match combination {
+                    Combination::Steam => {
+                        let steam: Box<dyn Object> = Box::new(SteamObject::new(
+                            contact,
+                            object.vector() + other.vector(),
+                            state.ticker,
+                        ));
+                        display.enqueue_action(RenderAction::Create {
+                            symbol: steam.symbol(),
+                            color: steam.color(),
+                            location: steam.location(),
                         });
-                        state.objects.push(object);
+                        object_grid.insert(objects.len(), steam.location());
+                        objects.push(Some(steam));
+                    }
+                    Combination::Nullify => {}
+                }
+                continue;
+            }
+
+            let mut consumed = false;
+
+            // Resolve hits in the order the object reaches them along its path
+            let mut hits: Vec<(f64, (u64, Hitbox), Point2<f64>)> = monster_grid
+                .along(old_pos, next_pos, object.hitbox().reach() + MAX_UNIT_REACH)
+                .into_iter()
+                .filter(|_| !object.hostile())
+                .filter_map(|(key, location)| {
+                    let t = sweep(old_pos, next_pos, object.hitbox(), location, key.1)?;
+                    Some((t, key, location))
+                })
+                .collect();
+            hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            for (_, key, location) in hits {
+                let id = key.0;
+                let Some(&monster_ix) = monster_index.get(&id) else {
+                    continue;
+                };
+                // Piercing objects hurt each monster once on their way through
+                if !object.strike(id) {
+                    continue;
+                }
+                let monster = &mut state.monsters[monster_ix];
+
+                let outcome = object
+                    .element()
+                    .map(|element| impact(element, monster.aura(state.ticker)))
+                    .unwrap_or(Impact::Kill);
+                let damage = match outcome {
+                    Impact::Kill => object.damage(),
+                    // Breaking the ice hurts twice as much
+                    Impact::Shatter => object.damage() * 2,
+                    Impact::Freeze | Impact::Dispel => 0,
+                };
+
+                match outcome {
+                    Impact::Freeze => {
+                        monster.aura =
+                            Some(Aura::new(Element::Frost, state.ticker, FROZEN_DURATION))
                     }
+                    Impact::Shatter | Impact::Dispel => monster.aura = None,
+                    Impact::Kill => {}
+                }
+                if outcome == Impact::Shatter {
+                    display.enqueue_action(RenderAction::Effect {
+                        effect: Effect::Explosion,
+                        color: object.color(),
+                        location: monster.location(),
+                    });
+                }
+
+                let fatal = damage > 0 && monster.take_hit(damage);
+                if fatal {
+                    monster_grid.remove(key, location);
+                    slay_monster(
+                        &mut state,
+                        &mut display,
+                        &mut monster_index,
+                        monster_ix,
+                        Some(object.get_spell()),
+                        if outcome == Impact::Shatter { 2 } else { 1 },
+                    );
                 } else {
-                    display.enqueue_action(RenderAction::Remove {
-                        coord: old_pos,
-                        symbol: object.symbol(),
+                    monster
+                        .body
+                        .push(object.vector(), object.knockback(), state.ticker);
+                    display.enqueue_action(RenderAction::Create {
+                        symbol: monster.symbol(),
+                        color: monster.color(),
+                        location: monster.location(),
+                    });
+                    display.enqueue_action(RenderAction::MonsterEffect {
+                        effect: Effect::Hit,
+                        color: object.color(),
+                        id: monster.id,
                     });
                 }
+
+                // The force of a shattering or killing hit carries on to the
+                // monsters around
+                let strength = match outcome {
+                    Impact::Shatter => SHATTER_BLAST,
+                    _ if fatal => object.knockback(),
+                    _ => 0.,
+                };
+                if strength > 0. {
+                    blast(
+                        &mut state,
+                        &monster_grid,
+                        &monster_index,
+                        location,
+                        BLAST_RADIUS,
+                        strength,
+                    );
+                }
+
+                if !object.pierces() {
+                    consumed = true;
+                    break;
+                }
             }
+
+            if consumed {
+                object_grid.remove(object_ix, old_pos);
+                display.enqueue_action(RenderAction::Remove {
+                    coord: old_pos,
+                    symbol: object.symbol(),
+                });
+                continue;
+            }
+
+            if old_pos.as_coord() != next_pos.as_coord() {
+                display.enqueue_action(RenderAction::Move {
+                    symbol: object.symbol(),
+                    color: object.color(),
+                    old: old_pos,
+                    new: next_pos,
+                });
+                display.enqueue_action(RenderAction::Effect {
+                    effect: Effect::Trail,
+                    color: object.color(),
+                    location: old_pos,
+                });
+                object_grid.relocate(object_ix, old_pos, next_pos);
+                object.set_location(next_pos, state.ticker);
+            }
+            objects[object_ix] = Some(object);
         }
+        state.objects = objects.into_iter().flatten().collect();
 
         // PLAYER
 
-        let (input_state, mouse_coord) = input_tracker.calculate_state();
+        let mouse_coord = frame.mouse;
 
         let mut step: Vector2<f64> = Vector2::zeros();
 
-        for key_state in input_state {
-            match key_state.as_command() {
-                Some(Command::Move(direction)) => {
+        // The dead no longer act
+        let commands = if state.player.is_dead() {
+            &[][..]
+        } else {
+            &frame.commands[..]
+        };
+
+        for command in commands.iter().copied() {
+            match command {
+                Command::Move(direction) => {
                     step += direction.as_vector();
                 }
-                Some(Command::Evoke(direction)) => {
-                    if state.player.active_spell_can_evoke(state.ticker) {
-                        let mut objects = state
-                            .player
-                            .active_spell_evoke(direction.as_vector(), state.ticker);
-
-                        while let Some(object) = objects.pop() {
-                            let location = object.location();
+                Command::Evoke(direction) if state.player.active_spell_can_evoke(state.ticker) => {
+                    let evocation = state
+                        .player
+                        .active_spell_evoke(direction.as_vector(), state.ticker);
 
-                            if location.x > 0.
-                                && location.x < bounds.x
-                                && location.y > 0.
-                                && location.y < bounds.y
-                            {
-                                display.enqueue_action(RenderAction::Create {
-                                    symbol: object.symbol(),
-                                    color: object.color(),
-                                    location,
-                                });
-
-                                state.objects.push(object);
-                            }
-                        }
-                    }
+                    spawn_evocation(&mut state, &mut display, evocation, bounds);
                 }
-                Some(Command::EvokeMouse) => {
-                    if state.player.active_spell_can_evoke(state.ticker) {
-                        let mut objects = state.player.active_spell_evoke(
-                            (mouse_coord - state.player.location).normalize(),
-                            state.ticker,
-                        );
-
-                        while let Some(object) = objects.pop() {
-                            let location = object.location();
+                Command::EvokeMouse if state.player.active_spell_can_evoke(state.ticker) => {
+                    let evocation = state
+                        .player
+                        .active_spell_evoke(mouse_coord - state.player.location, state.ticker);
 
-                            if location.x > 0.
-                                && location.x < bounds.x
-                                && location.y > 0.
-                                && location.y < bounds.y
-                            {
-                                display.enqueue_action(RenderAction::Create {
-                                    symbol: object.symbol(),
-                                    color: object.color(),
-                                    location,
-                                });
+                    spawn_evocation(&mut state, &mut display, evocation, bounds);
+                }
+                Command::EvokeSecondary
+                    if state
+                        .player
+                        .spell_can_evoke(state.player.secondary_spell, state.ticker) =>
+                {
+                    let evocation = state.player.spell_evoke(
+                        state.player.secondary_spell,
+                        mouse_coord - state.player.location,
+                        state.ticker,
+                    );
 
-                                state.objects.push(object);
-                            }
-                        }
-                    }
+                    spawn_evocation(&mut state, &mut display, evocation, bounds);
                 }
-                Some(Command::CycleSpell(false)) => {
+                Command::CycleSpell(false) => {
                     state.player.active_spell =
                         (state.player.active_spell + state.player.spells.len() - 1)
                             % state.player.spells.len()
                 }
-                Some(Command::CycleSpell(true)) => {
+                Command::CycleSpell(true) => {
                     state.player.active_spell =
                         (state.player.active_spell + 1) % state.player.spells.len()
                 }
-                Some(Command::SelectSpell(index)) => {
-                    if index < state.player.spells.len() {
-                        state.player.active_spell = index;
+                Command::SelectSpell(index) if index < state.player.spells.len() => {
+                    state.player.active_spell = index;
+                }
+                Command::BindSecondary => {
+                    state.player.secondary_spell = state.player.active_spell;
+                }
+                Command::UseItem(item) => {
+                    state.player.use_item(item);
+                }
+                Command::Dash => {
+                    state.player.dash(state.ticker);
+                }
+                Command::Swing if state.player.can_swing(state.ticker) => {
+                    state.player.swing(state.ticker);
+
+                    for (key, location) in
+                        monster_grid.nearby(state.player.location(), SWING_RANGE + MAX_UNIT_REACH)
+                    {
+                        if !state.player.in_swing_arc(location, key.1) {
+                            continue;
+                        }
+                        let Some(&monster_ix) = monster_index.get(&key.0) else {
+                            continue;
+                        };
+                        let monster = &mut state.monsters[monster_ix];
+
+                        if monster.take_hit(SWING_DAMAGE) {
+                            monster_grid.remove(key, location);
+                            slay_monster(
+                                &mut state,
+                                &mut display,
+                                &mut monster_index,
+                                monster_ix,
+                                None,
+                                1,
+                            );
+                            continue;
+                        }
+
+                        display.enqueue_action(RenderAction::MonsterEffect {
+                            effect: Effect::Hit,
+                            color: Color::White,
+                            id: monster.id,
+                        });
+                        let push = (monster.location() - state.player.location())
+                            .try_normalize(f64::EPSILON)
+                            .unwrap_or(state.player.facing);
+                        monster.body.push(push, SWING_KNOCKBACK, state.ticker);
                     }
                 }
                 _ => {}
@@ -364,12 +858,26 @@ fn game(stdout: &mut io::Stdout) -> io::Result<i32> {
         }
 
         if step != Vector2::zeros() {
+            state.player.face(step);
+
             let prev_pos = state.player.location();
             let next_pos = state.player.next_location(step, state.ticker);
 
             if next_pos.x > 0. && next_pos.x < bounds.x && next_pos.y > 0. && next_pos.y < bounds.y
             {
-                state.player.set_location(next_pos, state.ticker);
+                // Walking stops short of the first monster in the way
+                let hitbox = state.player.hitbox();
+                let t = monster_grid
+                    .along(prev_pos, next_pos, hitbox.reach() + MAX_UNIT_REACH)
+                    .into_iter()
+                    .filter_map(|((_, other), location)| {
+                        blocking(prev_pos, next_pos, hitbox, location, other)
+                    })
+                    .min_by(f64::total_cmp)
+                    .unwrap_or(1.);
+                state
+                    .player
+                    .set_location(prev_pos + (next_pos - prev_pos) * t, state.ticker);
 
                 display.enqueue_action(RenderAction::Move {
                     symbol: state.player.symbol(),
@@ -380,44 +888,245 @@ fn game(stdout: &mut io::Stdout) -> io::Result<i32> {
             } else {
                 state.player.set_ticker(state.ticker);
             }
+
+            let hitbox = state.player.hitbox();
+            let next_pos = state.player.location();
+            let mut picked: Vec<usize> = state
+                .item_grid
+                .along(prev_pos, next_pos, hitbox.reach() + PICKUP_HITBOX.reach())
+                .into_iter()
+                // Not what was already underfoot, like gear that was just swapped out
+                .filter(|(_, location)| {
+                    sweep(prev_pos, next_pos, hitbox, *location, PICKUP_HITBOX)
+                        .is_some_and(|t| t > 0.)
+                })
+                .map(|(ix, _)| ix)
+                .collect();
+            // Taken from the back so the pickups swapped in keep their slots
+            picked.sort_unstable_by(|a, b| b.cmp(a));
+            for ix in picked {
+                let pickup = take_item(&mut state, ix);
+                display.enqueue_action(RenderAction::Remove {
+                    coord: pickup.location(),
+                    symbol: pickup.symbol(),
+                });
+                if let Some(old) = state.player.pick_up(pickup.item) {
+                    drop_gear(&mut state, &mut display, pickup.location(), old);
+                }
+            }
         } else {
             state.player.charge_energy(state.ticker);
         }
+        state.player.decay_combo(state.ticker);
 
-        // MONSTERS
+        if state.player.body.is_moving() {
+            let prev_pos = state.player.location();
+            let displacement = state.player.body.step(state.ticker);
+            let next_pos = state.player.body.confine(prev_pos + displacement, bounds);
+
+            let hitbox = state.player.hitbox();
+            let blocker = monster_grid
+                .along(prev_pos, next_pos, hitbox.reach() + MAX_UNIT_REACH)
+                .into_iter()
+                .filter_map(|((id, other), location)| {
+                    Some((blocking(prev_pos, next_pos, hitbox, location, other)?, id))
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+
+            if let Some((t, id)) = blocker {
+                let velocity = state.player.body.stop();
+                if let Some(&monster_ix) = monster_index.get(&id) {
+                    state.monsters[monster_ix]
+                        .body
+                        .absorb(velocity * 0.5, state.ticker);
+                }
+
+                let stop_pos = prev_pos + (next_pos - prev_pos) * t;
+                if prev_pos.as_coord() != stop_pos.as_coord() {
+                    display.enqueue_action(RenderAction::Move {
+                        symbol: state.player.symbol(),
+                        color: state.player.color(),
+                        old: prev_pos,
+                        new: stop_pos,
+                    });
+                }
+                state.player.location = stop_pos;
+            } else if prev_pos.as_coord() != next_pos.as_coord() {
+                state.player.location = next_pos;
+                display.enqueue_action(RenderAction::Move {
+                    symbol: state.player.symbol(),
+                    color: state.player.color(),
+                    old: prev_pos,
+                    new: next_pos,
+                });
+            } else {
+                state.player.location = next_pos;
+            }
+        }
+
+        // ALLIES
+
+        for ally_ix in (0..state.allies.len()).rev() {
+            let ally = &mut state.allies[ally_ix];
+            let old_pos = ally.location();
+
+            if ally.expired(state.ticker) {
+                display.enqueue_action(RenderAction::Remove {
+                    coord: old_pos,
+                    symbol: ally.symbol(),
+                });
+                display.enqueue_action(RenderAction::Effect {
+                    effect: Effect::DeathPuff,
+                    color: ally.color(),
+                    location: old_pos,
+                });
+                state.allies.remove(ally_ix);
+                continue;
+            }
+
+            let target = monster_grid
+                .nearby(old_pos, WRAITH_SIGHT)
+                .into_iter()
+                .map(|(_, location)| location)
+                .min_by(|a, b| {
+                    (a - old_pos)
+                        .magnitude()
+                        .total_cmp(&(b - old_pos).magnitude())
+                });
+            let Some(target) = target else {
+                ally.set_ticker(state.ticker);
+                continue;
+            };
+
+            let next_pos = ally.seek(target, state.ticker);
+            if old_pos.as_coord() != next_pos.as_coord() {
+                display.enqueue_action(RenderAction::Move {
+                    symbol: ally.symbol(),
+                    color: ally.color(),
+                    old: old_pos,
+                    new: next_pos,
+                });
+            }
+            ally.set_location(next_pos, state.ticker);
+
+            if !ally.can_strike(state.ticker) {
+                continue;
+            }
+            let hitbox = ally.hitbox();
+            let struck = monster_grid
+                .along(old_pos, next_pos, hitbox.reach() + MAX_UNIT_REACH)
+                .into_iter()
+                .filter_map(|(key, location)| {
+                    Some((
+                        sweep(old_pos, next_pos, hitbox, location, key.1)?,
+                        key,
+                        location,
+                    ))
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            let Some((_, key, location)) = struck else {
+                continue;
+            };
+            ally.strike(state.ticker);
+            let spell = ally.spell;
+
+            let Some(&monster_ix) = monster_index.get(&key.0) else {
+                continue;
+            };
+            let monster = &mut state.monsters[monster_ix];
+            if monster.take_hit(WRAITH_DAMAGE) {
+                monster_grid.remove(key, location);
+                slay_monster(
+                    &mut state,
+                    &mut display,
+                    &mut monster_index,
+                    monster_ix,
+                    Some(spell),
+                    1,
+                );
+                continue;
+            }
 
-        let monsters_len = state.monsters.len();
+            display.enqueue_action(RenderAction::MonsterEffect {
+                effect: Effect::Hit,
+                color: Color::White,
+                id: monster.id,
+            });
+            monster.body.push(
+                monster.location() - next_pos,
+                WRAITH_KNOCKBACK,
+                state.ticker,
+            );
+        }
+
+        // MONSTERS
 
-        for monster_ix in (0..monsters_len).rev() {
-            let mut monster = state.monsters.remove(monster_ix);
+        for monster_ix in 0..state.monsters.len() {
+            let monster = &mut state.monsters[monster_ix];
             let old_pos = monster.location();
+            // Blocked monsters pass their momentum on once done with themselves
+            let mut shove = None;
+
+            if monster.aura.is_some_and(|aura| !aura.active(state.ticker)) {
+                monster.aura = None;
+                display.enqueue_action(RenderAction::Create {
+                    symbol: monster.symbol(),
+                    color: monster.color(),
+                    location: old_pos,
+                });
+            }
 
-            if let Some(mut next_pos) = monster.seek(state.player.location(), state.ticker) {
+            // Knocked back monsters are staggered until they come to rest
+            if monster.body.is_moving() {
+                let displacement = monster.body.step(state.ticker);
+                let next_pos = monster.body.confine(old_pos + displacement, bounds);
+
+                let hitbox = monster.hitbox();
+                let blocker = monster_grid
+                    .along(old_pos, next_pos, hitbox.reach() + MAX_UNIT_REACH)
+                    .into_iter()
+                    .filter(|((id, _), _)| *id != monster.id)
+                    .filter_map(|((id, other), location)| {
+                        Some((blocking(old_pos, next_pos, hitbox, location, other)?, id))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+
+                if let Some((_, id)) = blocker {
+                    shove = Some((id, monster.body.stop()));
+                    monster.set_ticker(state.ticker);
+                } else {
+                    if old_pos.as_coord() != next_pos.as_coord() {
+                        display.enqueue_action(RenderAction::Move {
+                            symbol: monster.symbol(),
+                            color: monster.color(),
+                            old: old_pos,
+                            new: next_pos,
+                        });
+                    }
+                    monster_grid.relocate((monster.id, monster.hitbox()), old_pos, next_pos);
+                    monster.set_location(next_pos, state.ticker);
+                }
+            } else if let Some(mut next_pos) = monster.seek(state.player.location(), state.ticker) {
                 let old_coord = old_pos.as_coord();
                 let next_coord = next_pos.as_coord();
 
                 if next_coord != old_coord {
-                    let mut collision = false;
-                    if next_pos.x > 0.
+                    let collision = if next_pos.x > 0.
                         && next_pos.x < bounds.x
                         && next_pos.y > 0.
                         && next_pos.y < bounds.y
                     {
-                        for other_ix in 0..(monsters_len - 1) {
-                            let other_monster = &state.monsters[other_ix];
-                            if (other_monster.location() - next_pos).magnitude() < 1.2 {
-                                collision = true;
-                                break;
-                            }
-                        }
-
-                        if (monster.location() - state.player.location()).magnitude() < 1. {
-                            state.score = 0;
-                            exit = true;
-                        }
+                        let hitbox = monster.hitbox();
+                        monster_grid
+                            .along(old_pos, next_pos, hitbox.reach() + MAX_UNIT_REACH)
+                            .into_iter()
+                            .filter(|((id, _), _)| *id != monster.id)
+                            .any(|((_, other), location)| {
+                                blocking(old_pos, next_pos, hitbox, location, other).is_some()
+                            })
                     } else {
-                        collision = true;
-                    }
+                        true
+                    };
 
                     if !collision {
                         display.enqueue_action(RenderAction::Move {
@@ -430,17 +1139,59 @@ fn game(stdout: &mut io::Stdout) -> io::Result<i32> {
                         next_pos = old_pos;
                     }
                 }
+                monster_grid.relocate((monster.id, monster.hitbox()), old_pos, next_pos);
                 monster.set_location(next_pos, state.ticker);
             } else {
                 monster.set_ticker(state.ticker);
             }
-            state.monsters.push(monster);
+
+            let venom = monster.spit(state.player.location(), state.ticker);
+
+            if !monster.is_frozen(state.ticker)
+                && sweep(
+                    old_pos,
+                    monster.location(),
+                    monster.hitbox(),
+                    state.player.location(),
+                    state.player.hitbox(),
+                )
+                .is_some()
+                && state
+                    .player
+                    .take_damage(MONSTER_CONTACT_DAMAGE, state.ticker)
+            {
+                display.enqueue_action(RenderAction::Effect {
+                    effect: Effect::HurtFlash,
+                    color: Color::Red,
+                    location: state.player.location(),
+                });
+                let push = state.player.location() - monster.location();
+                state
+                    .player
+                    .body
+                    .push(push, CONTACT_KNOCKBACK, state.ticker);
+
+                if state.player.is_dead() {
+                    cause_of_death.get_or_insert_with(|| format!("Killed by {}", monster.symbol()));
+                }
+            }
+
+            if let Some((id, velocity)) = shove {
+                if let Some(&other_ix) = monster_index.get(&id) {
+                    state.monsters[other_ix]
+                        .body
+                        .absorb(velocity * 0.5, state.ticker);
+                }
+            }
+            if let Some(venom) = venom {
+                spawn_objects(&mut state, &mut display, vec![Box::new(venom)], bounds);
+            }
         }
 
         // SPAWN MONSTERS
 
         if state.monsters.len() < 3 && state.ticker.saturating_sub(last_spawn_tick) >= 5_000 {
-            let monster = Monster::new(Point2::new(4., 4.), state.ticker, None, None);
+            let monster = Monster::new_simple(Point2::new(4., 4.), state.ticker, &mut state.rng);
 
             display.enqueue_action(RenderAction::Create {
                 symbol: monster.symbol(),
@@ -453,13 +1204,495 @@ fn game(stdout: &mut io::Stdout) -> io::Result<i32> {
             last_spawn_tick = state.ticker;
         }
 
+        // The death animation plays out over the following ticks while the
+        // world carries on
+        let death_frame = state.player.is_dead().then(|| {
+            let died = *died.get_or_insert(state.ticker);
+            (state.ticker.saturating_sub(died) / DEATH_FRAME_DURATION) as usize
+        });
+        if let Some(symbol) =
+            death_frame.and_then(|frame| Glyphs::current().death().into_iter().nth(frame))
+        {
+            display.enqueue_action(RenderAction::Create {
+                symbol,
+                color: Color::Red,
+                location: state.player.location(),
+            });
+        }
+
         // DRAWING
         display.draw(&state)?;
 
-        if (state.player.location() - Point2::new(1., 1.)).magnitude() < 1. || exit {
+        if death_frame.is_some_and(|frame| frame >= Glyphs::current().death().len()) {
+            display.draw_summary(
+                "Game Over",
+                &[
+                    cause_of_death.unwrap_or_else(|| String::from("Died")),
+                    format!("Floor reached: {}", state.floor),
+                    format!("Final score: {}", state.score),
+                ],
+            )?;
+            menu::wait_for_key()?;
             break;
         }
+
+        if exit {
+            break;
+        }
+
+        if state.player.is_dead() {
+            continue;
+        }
+
+        // Unclaimed rewards carry over to the next level up
+        if state.player.experience.level > offered_level {
+            offered_level = state.player.experience.level;
+
+            let menu_start = timer.elapsed().as_millis();
+            choose_rewards(
+                &mut display,
+                &keymap,
+                &mut session,
+                &mut state.player,
+                &mut state.rng,
+            )?;
+            pause_ticker += timer.elapsed().as_millis() - menu_start;
+
+            input_tracker.reset();
+            display.draw_initial(&state)?;
+        }
+
+        if overlaps(
+            state.player.location(),
+            state.player.hitbox(),
+            DOOR,
+            DOOR_HITBOX,
+        ) {
+            let menu_start = timer.elapsed().as_millis();
+            choose_upgrades(
+                &mut display,
+                &keymap,
+                &mut session,
+                &mut state.player,
+                &mut state.rng,
+            )?;
+            pause_ticker += timer.elapsed().as_millis() - menu_start;
+
+            state.floor += 1;
+            populate_floor(&mut state, bounds);
+            input_tracker.reset();
+            display.draw_initial(&state)?;
+        }
     }
 
     Ok(state.score)
 }
+
+const ITEM_DROP_CHANCE: f64 = 0.2;
+// Narrower frames have the level and combo indicators run into each other
+const MIN_GAME_BOUNDS: Vector2<u16> = Vector2::new(19, 10);
+const GRID_CELL_SIZE: f64 = 2.;
+const DOOR: Point2<f64> = Point2::new(1., 1.);
+const DOOR_HITBOX: Hitbox = Hitbox::circle(0.5);
+const ITEMS_PER_FLOOR: usize = 2;
+const DEATH_FRAME_DURATION: u128 = 500;
+const BLAST_RADIUS: f64 = 2.5;
+const SHATTER_BLAST: f64 = 2.;
+const COMBINATION_BLAST: f64 = 1.5;
+
+// Holds until the terminal fits the frame again, None if quit meanwhile
+fn wait_for_room(display: &mut impl Display, keymap: &Keymap) -> io::Result<Option<(u16, u16)>> {
+    loop {
+        match read()? {
+            Event::Resize(cols, rows) => {
+                if display.resize(cols, rows)? {
+                    return Ok(Some((cols, rows)));
+                }
+            }
+            event => {
+                if keymap.system_command(&event) == Some(Command::Quit) {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+fn spawn_objects(
+    state: &mut State,
+    display: &mut impl Display,
+    objects: Vec<Box<dyn Object>>,
+    bounds: Vector2<f64>,
+) {
+    for object in objects {
+        let location = object.location();
+        debug_assert!(object.hitbox().reach() <= MAX_OBJECT_REACH);
+
+        if object.due() > state.ticker {
+            state.queued.push(object);
+        } else if location.x > 0.
+            && location.x < bounds.x
+            && location.y > 0.
+            && location.y < bounds.y
+        {
+            display.enqueue_action(RenderAction::Create {
+                symbol: object.symbol(),
+                color: object.color(),
+                location,
+            });
+
+            state.objects.push(object);
+        }
+    }
+}
+
+fn spawn_evocation(
+    state: &mut State,
+    display: &mut impl Display,
+    evocation: Evocation,
+    bounds: Vector2<f64>,
+) {
+    spawn_objects(state, display, evocation.objects, bounds);
+
+    for mut ally in evocation.allies {
+        ally.location = state.player.body.confine(ally.location, bounds);
+        display.enqueue_action(RenderAction::Create {
+            symbol: ally.symbol(),
+            color: ally.color(),
+            location: ally.location(),
+        });
+        state.allies.push(ally);
+    }
+}
+
+// Pushes the monsters around an explosion away from it
+fn blast(
+    state: &mut State,
+    monster_grid: &SpatialGrid<(u64, Hitbox)>,
+    monster_index: &HashMap<u64, usize>,
+    center: Point2<f64>,
+    radius: f64,
+    strength: f64,
+) {
+    for ((id, _), location) in monster_grid.nearby(center, radius) {
+        let distance = blast_distance(center, location, radius, strength);
+        if let Some(&monster_ix) = monster_index.get(&id) {
+            state.monsters[monster_ix]
+                .body
+                .push(location - center, distance, state.ticker);
+        }
+    }
+}
+
+fn slay_monster(
+    state: &mut State,
+    display: &mut impl Display,
+    monster_index: &mut HashMap<u64, usize>,
+    monster_ix: usize,
+    spell: Option<Spell>,
+    score: i32,
+) {
+    let monster = state.monsters.swap_remove(monster_ix);
+    monster_index.remove(&monster.id);
+    // The last monster took over the freed slot
+    if let Some(moved) = state.monsters.get(monster_ix) {
+        monster_index.insert(moved.id, monster_ix);
+    }
+    state.score += score;
+    state
+        .player
+        .reward_kill(spell, monster.archetype.xp(), state.ticker);
+
+    display.enqueue_action(RenderAction::Remove {
+        coord: monster.location(),
+        symbol: monster.symbol(),
+    });
+    display.enqueue_action(RenderAction::Effect {
+        effect: Effect::DeathPuff,
+        color: monster.color(),
+        location: monster.location(),
+    });
+
+    if state.rng.gen::<f64>() < ITEM_DROP_CHANCE {
+        let pickup = Pickup::new(
+            monster.location(),
+            *CONSUMABLES.choose(&mut state.rng).unwrap(),
+        );
+        display.enqueue_action(RenderAction::Create {
+            symbol: pickup.symbol(),
+            color: pickup.color(),
+            location: pickup.location(),
+        });
+        drop_item(state, pickup);
+    }
+}
+
+fn drop_item(state: &mut State, pickup: Pickup) {
+    state.item_grid.insert(state.items.len(), pickup.location());
+    state.items.push(pickup);
+}
+
+fn drop_gear(state: &mut State, display: &mut impl Display, location: Point2<f64>, gear: Gear) {
+    let pickup = Pickup::new(location, Item::Gear(gear));
+    display.enqueue_action(RenderAction::Create {
+        symbol: pickup.symbol(),
+        color: pickup.color(),
+        location: pickup.location(),
+    });
+    drop_item(state, pickup);
+}
+
+// The last pickup takes over the freed slot, in the grid as well
+fn take_item(state: &mut State, ix: usize) -> Pickup {
+    let pickup = state.items.swap_remove(ix);
+    state.item_grid.remove(ix, pickup.location());
+    if let Some(moved) = state.items.get(ix) {
+        state
+            .item_grid
+            .relocate_key(state.items.len(), ix, moved.location());
+    }
+    pickup
+}
+
+fn apply_dev_command(
+    state: &mut State,
+    display: &mut impl Display,
+    command: DevCommand,
+    bounds: Vector2<f64>,
+) {
+    match command {
+        DevCommand::Spawn(archetype, location) => {
+            let location = location.unwrap_or(state.player.location() + state.player.facing * 3.);
+            let location = Point2::new(
+                location.x.clamp(1., bounds.x - 1.),
+                location.y.clamp(1., bounds.y - 1.),
+            );
+            let monster = Monster::new(location, state.ticker, archetype, &mut state.rng);
+
+            display.enqueue_action(RenderAction::Create {
+                symbol: monster.symbol(),
+                color: monster.color(),
+                location: monster.location(),
+            });
+            state.monsters.push(monster);
+        }
+        DevCommand::God => {
+            state.player.god_mode = !state.player.god_mode;
+        }
+        DevCommand::Energy(energy) => {
+            state.player.energy = energy.min(state.player.max_energy());
+        }
+        DevCommand::Give(Gift::Spell(spell)) => {
+            if state
+                .player
+                .spells
+                .iter()
+                .all(|magic| magic.get_spell() != spell)
+            {
+                state.player.spells.extend(spell.create_ability());
+            }
+        }
+        DevCommand::Give(Gift::Item(item)) => {
+            if let Some(old) = state.player.pick_up(item) {
+                let location = state.player.location();
+                drop_gear(state, display, location, old);
+            }
+        }
+        DevCommand::Teleport(location) => {
+            let old = state.player.location();
+            let new = state.player.body.confine(location, bounds);
+            state.player.location = new;
+
+            display.enqueue_action(RenderAction::Move {
+                symbol: state.player.symbol(),
+                color: state.player.color(),
+                old,
+                new,
+            });
+        }
+        // The clock was already adjusted when it was entered
+        DevCommand::Speed(_) => {}
+        DevCommand::KillAll => {
+            for monster in state.monsters.drain(..) {
+                display.enqueue_action(RenderAction::Remove {
+                    coord: monster.location(),
+                    symbol: monster.symbol(),
+                });
+            }
+        }
+    }
+}
+
+fn populate_floor(state: &mut State, bounds: Vector2<f64>) {
+    let ticker = state.ticker;
+    let center = Point2::new(bounds.x / 2., bounds.y / 2.);
+
+    state.objects.clear();
+    state.queued.clear();
+    state.allies.clear();
+    state.items.clear();
+    state.item_grid = SpatialGrid::new(GRID_CELL_SIZE);
+    state.player.set_location(center, ticker);
+    state.monsters = vec![
+        Monster::new_simple(
+            Point2::new(bounds.x / 4., bounds.y / 4.),
+            ticker,
+            &mut state.rng,
+        ),
+        Monster::new(
+            Point2::new(bounds.x * 3. / 4., bounds.y / 4.),
+            ticker,
+            Archetype::Stalker,
+            &mut state.rng,
+        ),
+        Monster::new(
+            Point2::new(bounds.x * 3. / 4., bounds.y * 3. / 4.),
+            ticker,
+            Archetype::Lurker,
+            &mut state.rng,
+        ),
+        Monster::new(
+            Point2::new(bounds.x / 4., bounds.y * 3. / 4.),
+            ticker,
+            Archetype::Drifter,
+            &mut state.rng,
+        ),
+    ];
+
+    // Small arenas shrink the space kept clear around the player
+    let keep_away = 5_f64.min(bounds.x.min(bounds.y) / 4.);
+    // Every other extra monster spits from a distance, every third is a troll
+    // and every fifth a dragon
+    for i in 1..state.floor {
+        let location = (0..100)
+            .map(|_| {
+                Point2::new(
+                    1. + state.rng.gen::<f64>() * (bounds.x - 2.),
+                    1. + state.rng.gen::<f64>() * (bounds.y - 2.),
+                )
+            })
+            .find(|location| (location - center).magnitude() > keep_away)
+            .unwrap_or(Point2::new(1., 1.));
+        let archetype = if i % 5 == 0 {
+            Archetype::Dragon
+        } else if i % 3 == 0 {
+            Archetype::Troll
+        } else if i % 2 == 0 {
+            Archetype::Spitter
+        } else {
+            Archetype::Wanderer
+        };
+        state
+            .monsters
+            .push(Monster::new(location, ticker, archetype, &mut state.rng));
+    }
+
+    let mut items: Vec<Item> = (0..ITEMS_PER_FLOOR)
+        .map(|_| *CONSUMABLES.choose(&mut state.rng).unwrap())
+        .collect();
+    items.push(Item::Gear(*GEAR.choose(&mut state.rng).unwrap()));
+    for item in items {
+        let location = Point2::new(
+            1. + state.rng.gen::<f64>() * (bounds.x - 2.),
+            1. + state.rng.gen::<f64>() * (bounds.y - 2.),
+        );
+        drop_item(state, Pickup::new(location, item));
+    }
+}
+
+fn choose_upgrades(
+    display: &mut impl Display,
+    keymap: &Keymap,
+    session: &mut Session,
+    player: &mut Player,
+    rng: &mut StdRng,
+) -> io::Result<()> {
+    loop {
+        let candidates: Vec<(usize, Upgrade)> = player
+            .spells
+            .iter()
+            .enumerate()
+            .filter(|(_, spell)| spell.progression().unspent > 0)
+            .flat_map(|(ix, spell)| spell.upgrades().into_iter().map(move |u| (ix, u)))
+            .collect();
+
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        let choices: Vec<(usize, Upgrade)> = candidates.choose_multiple(rng, 3).copied().collect();
+
+        let options: Vec<String> = choices
+            .iter()
+            .map(|(ix, upgrade)| {
+                let spell = &player.spells[*ix];
+                format!(
+                    "[{}] {} {:?} lv{}: {}",
+                    keymap.label(Command::SelectSpell(*ix)),
+                    spell.get_spell().as_symbol(),
+                    spell.get_spell(),
+                    spell.progression().level,
+                    upgrade.description()
+                )
+            })
+            .collect();
+
+        match session.choose(
+            display,
+            keymap,
+            "Floor cleared! Choose an upgrade",
+            &options,
+        )? {
+            Some(choice) => {
+                let (ix, upgrade) = choices[choice];
+                player.spells[ix].progression_mut().apply(upgrade);
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+fn choose_rewards(
+    display: &mut impl Display,
+    keymap: &Keymap,
+    session: &mut Session,
+    player: &mut Player,
+    rng: &mut StdRng,
+) -> io::Result<()> {
+    while player.experience.unspent > 0 {
+        let unknown_spells: Vec<Spell> = player
+            .class
+            .learnable()
+            .into_iter()
+            .filter(|spell| {
+                player
+                    .spells
+                    .iter()
+                    .all(|magic| magic.get_spell() != *spell)
+            })
+            .collect();
+
+        let choices: Vec<Reward> = STAT_REWARDS
+            .choose_multiple(rng, 2)
+            .copied()
+            .map(Reward::Stat)
+            .chain(unknown_spells.choose(rng).copied().map(Reward::Spell))
+            .collect();
+
+        let options: Vec<String> = choices
+            .iter()
+            .map(|reward| match reward {
+                Reward::Spell(spell) => format!("{} {}", spell.as_symbol(), reward.description()),
+                Reward::Stat(_) => reward.description(),
+            })
+            .collect();
+
+        let title = format!("Level {}! Choose a reward", player.experience.level);
+        match session.choose(display, keymap, &title, &options)? {
+            Some(choice) => player.claim_reward(choices[choice]),
+            None => return Ok(()),
+        }
+    }
+
+    Ok(())
+}
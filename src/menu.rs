@@ -0,0 +1,50 @@
+use std::io;
+
+use std::time::Duration;
+
+use crossterm::event::{poll, read, Event, KeyEvent, KeyEventKind};
+
+use crate::{command::Command, console::Keymap, display::Display};
+
+// Each option is picked with the spell key in its place
+pub fn labelled(keymap: &Keymap, options: &[String]) -> Vec<String> {
+    options
+        .iter()
+        .enumerate()
+        .map(|(ix, option)| format!("{}) {}", keymap.label(Command::SelectSpell(ix)), option))
+        .collect()
+}
+
+pub fn choose(
+    display: &mut impl Display,
+    keymap: &Keymap,
+    title: &str,
+    options: &[String],
+) -> io::Result<Option<usize>> {
+    display.draw_menu(title, &labelled(keymap, options))?;
+
+    loop {
+        match keymap.pressed(&read()?) {
+            Some(Command::Quit) => return Ok(None),
+            Some(Command::SelectSpell(ix)) if ix < options.len() => return Ok(Some(ix)),
+            _ => {}
+        }
+    }
+}
+
+pub fn wait_for_key() -> io::Result<()> {
+    // Drop whatever was still queued from gameplay
+    while poll(Duration::ZERO)? {
+        read()?;
+    }
+
+    loop {
+        if let Event::Key(KeyEvent {
+            kind: KeyEventKind::Press,
+            ..
+        }) = read()?
+        {
+            return Ok(());
+        }
+    }
+}
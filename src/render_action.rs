@@ -1,6 +1,16 @@
 use crossterm::style::Color;
 use nalgebra::Point2;
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Effect {
+    Explosion,
+    Hit,
+    Trail,
+    DeathPuff,
+    // Flashes the frame when the player is hurt
+    HurtFlash,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum RenderAction {
     Move {
@@ -18,4 +28,16 @@ pub enum RenderAction {
         color: Color,
         location: Point2<f64>,
     },
+    // Drawn over the board for a while, the board shows again once it ends
+    Effect {
+        effect: Effect,
+        color: Color,
+        location: Point2<f64>,
+    },
+    // Like `Effect`, but follows the monster with `id` while it lasts
+    MonsterEffect {
+        effect: Effect,
+        color: Color,
+        id: u64,
+    },
 }
@@ -0,0 +1,402 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{poll, read};
+use log::warn;
+use nalgebra::{Point2, Vector2};
+use time::OffsetDateTime;
+
+use crate::{
+    class::{Class, CLASSES},
+    command::Command,
+    console::Keymap,
+    dev_command::DevCommand,
+    display::Display,
+    magic::Spell,
+    menu,
+};
+
+const HEADER: &str = "rust_dungeon replay 1";
+const SPEEDS: [u128; 4] = [1, 2, 4, 8];
+// Long enough to read a menu before the recorded choice is taken
+const MENU_DELAY: Duration = Duration::from_millis(800);
+
+// Everything the simulation needs besides the commands to play out the same
+pub struct Settings {
+    pub seed: u64,
+    pub game_bounds: Vector2<u16>,
+    pub class: Class,
+    pub spells: Vec<Spell>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub ticker: u128,
+    pub commands: Vec<Command>,
+    // Typed into the developer console, applied before the tick
+    pub dev: Vec<DevCommand>,
+    pub mouse: Point2<f64>,
+}
+
+enum Entry {
+    Frame(Frame),
+    Choice(Option<usize>),
+}
+
+pub enum Playback {
+    Play(Frame),
+    Wait,
+    Stop,
+}
+
+pub enum Session {
+    Record(Recorder),
+    Replay(Replay),
+}
+
+impl Session {
+    // Menus during the game go through here so their choices replay as well
+    pub fn choose(
+        &mut self,
+        display: &mut impl Display,
+        keymap: &Keymap,
+        title: &str,
+        options: &[String],
+    ) -> io::Result<Option<usize>> {
+        match self {
+            Session::Record(recorder) => {
+                let choice = menu::choose(display, keymap, title, options)?;
+                recorder.choice(choice)?;
+                Ok(choice)
+            }
+            Session::Replay(replay) => {
+                display.draw_menu(title, &menu::labelled(keymap, options))?;
+                std::thread::sleep(MENU_DELAY);
+                Ok(replay.next_choice())
+            }
+        }
+    }
+}
+
+// Every run is kept under the time it started, e.g.
+// rust_dungeon-20240131-235959.replay
+pub fn replay_path() -> String {
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    format!(
+        "rust_dungeon-{}{:02}{:02}-{:02}{:02}{:02}.replay",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+// Lines are flushed as they happen so a crash still leaves the replay behind
+pub struct Recorder {
+    out: BufWriter<File>,
+    last_ticker: u128,
+    last_mouse: Option<Point2<f64>>,
+}
+
+impl Recorder {
+    pub fn create(path: &str, settings: &Settings) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{}", HEADER)?;
+        writeln!(out, "seed {}", settings.seed)?;
+        writeln!(
+            out,
+            "bounds {} {}",
+            settings.game_bounds.x, settings.game_bounds.y
+        )?;
+        writeln!(out, "class {:?}", settings.class)?;
+        let spells: Vec<String> = settings
+            .spells
+            .iter()
+            .map(|spell| format!("{:?}", spell))
+            .collect();
+        writeln!(out, "spells {}", spells.join(" "))?;
+
+        Ok(Self {
+            out,
+            last_ticker: 0,
+            last_mouse: None,
+        })
+    }
+
+    // One line per tick: elapsed ms, the commands and the cursor when it moved,
+    // preceded by a line for each developer command
+    pub fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        for command in &frame.dev {
+            writeln!(self.out, "> {}", command)?;
+        }
+        write!(self.out, "{}", frame.ticker - self.last_ticker)?;
+        self.last_ticker = frame.ticker;
+
+        for command in &frame.commands {
+            write!(self.out, " {}", command.name())?;
+        }
+        if self.last_mouse != Some(frame.mouse) {
+            write!(self.out, " @{},{}", frame.mouse.x, frame.mouse.y)?;
+            self.last_mouse = Some(frame.mouse);
+        }
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+
+    pub fn choice(&mut self, choice: Option<usize>) -> io::Result<()> {
+        match choice {
+            Some(ix) => writeln!(self.out, "? {}", ix)?,
+            None => writeln!(self.out, "? -")?,
+        }
+        self.out.flush()
+    }
+}
+
+pub struct Replay {
+    entries: VecDeque<Entry>,
+    paused: bool,
+    speed: usize,
+    // Recorded time the playback has caught up to
+    playback: u128,
+    last_update: Instant,
+}
+
+impl Replay {
+    pub fn load(path: &str) -> io::Result<(Settings, Self)> {
+        let content = fs::read_to_string(path)?;
+        let (settings, entries) =
+            parse(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok((
+            settings,
+            Self {
+                entries,
+                paused: false,
+                speed: 0,
+                playback: 0,
+                last_update: Instant::now(),
+            },
+        ))
+    }
+
+    // Handles playback controls and hands out the next frame once it is due
+    pub fn next_frame(&mut self, keymap: &Keymap) -> io::Result<Playback> {
+        let mut step = false;
+        let due = match self.entries.front() {
+            Some(Entry::Frame(frame)) => frame.ticker,
+            _ => return Ok(Playback::Stop),
+        };
+
+        let wait = if self.paused || due > self.playback {
+            Duration::from_millis(20)
+        } else {
+            Duration::ZERO
+        };
+        if poll(wait)? {
+            match keymap.system_command(&read()?) {
+                Some(Command::Quit) => return Ok(Playback::Stop),
+                Some(Command::Pause) => self.paused = !self.paused,
+                Some(Command::FastForward) => self.speed = (self.speed + 1) % SPEEDS.len(),
+                Some(Command::Step) => step = self.paused,
+                _ => {}
+            }
+        }
+
+        let elapsed = self.last_update.elapsed().as_millis();
+        self.last_update = Instant::now();
+        if !self.paused {
+            self.playback += elapsed * SPEEDS[self.speed];
+        }
+
+        if !step && (self.paused || due > self.playback) {
+            return Ok(Playback::Wait);
+        }
+
+        match self.entries.pop_front() {
+            Some(Entry::Frame(frame)) => {
+                if step {
+                    self.playback = frame.ticker;
+                }
+                Ok(Playback::Play(frame))
+            }
+            _ => Ok(Playback::Stop),
+        }
+    }
+
+    // Time spent away, e.g. waiting for the terminal to grow, is not played back
+    pub fn resume(&mut self) {
+        self.last_update = Instant::now();
+    }
+
+    fn next_choice(&mut self) -> Option<usize> {
+        match self.entries.pop_front() {
+            Some(Entry::Choice(choice)) => choice,
+            entry => {
+                warn!("Replay out of sync, expected a menu choice");
+                if let Some(entry) = entry {
+                    self.entries.push_front(entry);
+                }
+                None
+            }
+        }
+    }
+}
+
+fn parse(content: &str) -> Result<(Settings, VecDeque<Entry>), String> {
+    let mut lines = content.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(String::from("Not a replay file"));
+    }
+
+    let mut field = |name: &str| {
+        lines
+            .next()
+            .and_then(|line| line.strip_prefix(name))
+            .and_then(|line| line.strip_prefix(' '))
+            .ok_or(format!("Missing {}", name))
+    };
+
+    let seed = field("seed")?
+        .parse()
+        .map_err(|_| String::from("Invalid seed"))?;
+    let bounds: Vec<u16> = field("bounds")?
+        .split(' ')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| String::from("Invalid bounds"))?;
+    let [x, y] = bounds[..] else {
+        return Err(String::from("Invalid bounds"));
+    };
+    let class_name = field("class")?;
+    let class = *CLASSES
+        .iter()
+        .find(|class| format!("{:?}", class) == class_name)
+        .ok_or(format!("Unknown class: {}", class_name))?;
+    let spells = field("spells")?
+        .split(' ')
+        .map(|name| {
+            class
+                .learnable()
+                .into_iter()
+                .find(|spell| format!("{:?}", spell) == name)
+                .ok_or(format!("Unknown spell: {}", name))
+        })
+        .collect::<Result<Vec<Spell>, _>>()?;
+
+    let settings = Settings {
+        seed,
+        game_bounds: Vector2::new(x, y),
+        class,
+        spells,
+    };
+
+    let mut entries = VecDeque::new();
+    let mut ticker = 0;
+    let mut mouse = Point2::origin();
+    let mut dev = Vec::new();
+    for line in lines {
+        if let Some(command) = line.strip_prefix("> ") {
+            dev.push(command.parse()?);
+            continue;
+        }
+
+        let mut tokens = line.split(' ');
+        let first = tokens.next().unwrap_or_default();
+
+        if first == "?" {
+            let choice = tokens.next().and_then(|choice| choice.parse().ok());
+            entries.push_back(Entry::Choice(choice));
+            continue;
+        }
+
+        ticker += first
+            .parse::<u128>()
+            .map_err(|_| format!("Invalid line: {}", line))?;
+        let mut commands = Vec::new();
+        for token in tokens {
+            if let Some((x, y)) = token.strip_prefix('@').and_then(|m| m.split_once(',')) {
+                let (Ok(x), Ok(y)) = (x.parse(), y.parse()) else {
+                    return Err(format!("Invalid cursor: {}", token));
+                };
+                mouse = Point2::new(x, y);
+            } else {
+                commands
+                    .push(Command::from_name(token).ok_or(format!("Unknown command: {}", token))?);
+            }
+        }
+
+        entries.push_back(Entry::Frame(Frame {
+            ticker,
+            commands,
+            dev: std::mem::take(&mut dev),
+            mouse,
+        }));
+    }
+
+    Ok((settings, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{direction::Direction, item::Item};
+
+    use super::*;
+
+    #[test]
+    fn recorded_frames_parse_back() {
+        let settings = Settings {
+            seed: 42,
+            game_bounds: Vector2::new(29, 20),
+            class: Class::Ranger,
+            spells: vec![Spell::Arrow, Spell::Sphere],
+        };
+        let frames = [
+            Frame {
+                ticker: 20,
+                commands: vec![],
+                dev: vec![],
+                mouse: Point2::new(14.5, 10.),
+            },
+            Frame {
+                ticker: 41,
+                commands: vec![
+                    Command::Move(Direction::Left),
+                    Command::EvokeMouse,
+                    Command::UseItem(Item::HealthPotion),
+                ],
+                dev: vec![DevCommand::God, DevCommand::Teleport(Point2::new(3., 4.5))],
+                mouse: Point2::new(3.25, 7.1),
+            },
+        ];
+
+        let path = std::env::temp_dir().join(format!(
+            "rust_dungeon_test_{}_{}.replay",
+            std::process::id(),
+            OffsetDateTime::now_utc().unix_timestamp_nanos()
+        ));
+        let mut recorder = Recorder::create(path.to_str().unwrap(), &settings).unwrap();
+        recorder.frame(&frames[0]).unwrap();
+        recorder.choice(Some(1)).unwrap();
+        recorder.frame(&frames[1]).unwrap();
+
+        // Readable before the recorder is dropped
+        let content = fs::read_to_string(&path).unwrap();
+        drop(recorder);
+        let (parsed, mut entries) = parse(&content).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(parsed.seed, 42);
+        assert_eq!(parsed.game_bounds, settings.game_bounds);
+        assert_eq!(parsed.class, Class::Ranger);
+        assert_eq!(parsed.spells, settings.spells);
+
+        assert!(matches!(entries.pop_front(), Some(Entry::Frame(f)) if f == frames[0]));
+        assert!(matches!(entries.pop_front(), Some(Entry::Choice(Some(1)))));
+        assert!(matches!(entries.pop_front(), Some(Entry::Frame(f)) if f == frames[1]));
+    }
+}